@@ -0,0 +1,100 @@
+//! `#[derive(DmaWord)]` for user-defined DMA word types.
+//!
+//! Use it through the `derive` feature of `dma-poc`, which re-exports the
+//! macro next to the `DmaWord` trait.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Meta, NestedMeta};
+
+/// Derive `Word` and `DmaWord` for a `#[repr(transparent)]` newtype.
+///
+/// The type must be a struct with a single field, whose type implements
+/// `DmaWord` itself. Since the newtype has the same layout as that field,
+/// it is valid for every byte pattern the field is valid for.
+///
+/// ```ignore
+/// #[derive(Clone, Copy, DmaWord)]
+/// #[repr(transparent)]
+/// struct Sample(u16);
+/// ```
+#[proc_macro_derive(DmaWord)]
+pub fn derive_dma_word(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    let name = &input.ident;
+
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "`DmaWord` can only be derived for structs",
+            ))
+        }
+    };
+
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "`DmaWord` can't be derived for generic types",
+        ));
+    }
+
+    if !is_repr_transparent(input) {
+        return Err(Error::new_spanned(
+            name,
+            "`DmaWord` can only be derived for `#[repr(transparent)]` types",
+        ));
+    }
+
+    let mut fields = fields.iter();
+    let field = match (fields.next(), fields.next()) {
+        (Some(field), None) => field,
+        _ => {
+            return Err(Error::new_spanned(
+                name,
+                "`DmaWord` can only be derived for types with exactly one field",
+            ))
+        }
+    };
+    let ty = &field.ty;
+
+    Ok(quote! {
+        const _: () = {
+            // The wrapped type must be a valid DMA word itself.
+            fn assert_dma_word<T: ::dma_poc::DmaWord>() {}
+            const _: fn() = assert_dma_word::<#ty>;
+
+            unsafe impl ::dma_poc::Word for #name {
+                const SIZE: ::dma_poc::WordSize = <#ty as ::dma_poc::Word>::SIZE;
+            }
+
+            unsafe impl ::dma_poc::DmaWord for #name {}
+        };
+    })
+}
+
+fn is_repr_transparent(input: &DeriveInput) -> bool {
+    input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("repr"))
+        .filter_map(|attr| attr.parse_meta().ok())
+        .any(|meta| match meta {
+            Meta::List(list) => list.nested.iter().any(|nested| match nested {
+                NestedMeta::Meta(Meta::Path(path)) => path.is_ident("transparent"),
+                _ => false,
+            }),
+            _ => false,
+        })
+}
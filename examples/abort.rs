@@ -0,0 +1,59 @@
+//! This example demonstrates aborting a transfer and using the data that
+//! arrived until then.
+//!
+//! It receives from USART1 for about a second (at the default 8 MHz clock),
+//! then aborts the transfer. Connect some sender to the USART pins
+//! (PC4/PC5) that transmits at 9600 baud.
+
+#![no_std]
+#![no_main]
+
+use cortex_m::asm;
+use cortex_m_rt::entry;
+use cortex_m_semihosting::hprintln;
+use dma_poc::{DmaExt, Rx, Transfer};
+use stm32f3::stm32f303 as pac;
+
+static mut DST: [u8; 16] = [0; 16];
+
+#[entry]
+fn main() -> ! {
+    let mut device = pac::Peripherals::take().unwrap();
+    let channels = device.DMA1.split(&mut device.RCC);
+
+    setup_usart1(&device.RCC, &device.GPIOC, &device.USART1);
+    let rx = Rx::new(device.USART1);
+
+    let dst = unsafe { &mut DST };
+    let transfer = Transfer::from_peripheral(channels.ch5, rx, dst);
+
+    asm::delay(8_000_000);
+
+    let (dst, received) = match transfer.abort() {
+        Ok((_ch5, _rx, dst)) => {
+            let len = dst.len();
+            (dst, len)
+        }
+        Err(error) => (error.dst, error.transferred),
+    };
+
+    hprintln!("Received: {:?}", &dst[..received]).unwrap();
+    loop {
+        continue;
+    }
+}
+
+fn setup_usart1(rcc: &pac::RCC, gpioc: &pac::GPIOC, usart: &pac::USART1) {
+    rcc.ahbenr.modify(|_, w| w.iopcen().enabled());
+    rcc.apb2enr.modify(|_, w| w.usart1en().enabled());
+
+    // PC4 (TX) and PC5 (RX) in alternate function 7
+    gpioc
+        .moder
+        .modify(|_, w| w.moder4().alternate().moder5().alternate());
+    gpioc.afrl.modify(|_, w| w.afrl4().af7().afrl5().af7());
+
+    // 9600 baud at the default 8 MHz clock
+    usart.brr.write(|w| w.brr().bits((8_000_000 / 9600) as u16));
+    usart.cr1.modify(|_, w| w.re().enabled().ue().enabled());
+}
@@ -0,0 +1,26 @@
+//! This example demonstrates transferring into an over-aligned buffer.
+
+#![no_std]
+#![no_main]
+
+use cortex_m_rt::entry;
+use cortex_m_semihosting::hprintln;
+use dma_poc::{Aligned, Transfer, A32};
+
+const SRC: &[u8; 16] = b"THIS IS DMADATA!";
+static mut DST: Aligned<A32, [u8; 16]> = Aligned::new([0; 16]);
+
+#[entry]
+fn main() -> ! {
+    let dst = unsafe { &mut DST };
+    assert_eq!(dst.as_ptr() as usize % 32, 0);
+
+    let transfer = Transfer::start(SRC, dst);
+    let (_dma, src, dst) = transfer.wait().expect("Transfer error");
+    assert_eq!(src, &dst.value);
+
+    hprintln!("Transfer finished successfully").unwrap();
+    loop {
+        continue;
+    }
+}
@@ -0,0 +1,67 @@
+//! This example demonstrates awaiting a DMA transfer.
+//!
+//! It uses a minimal executor that sleeps until an interrupt arrives
+//! whenever the future is pending. Real applications would use a proper
+//! async executor instead.
+//!
+//! Requires the `async` feature: `cargo run --example async --features async`
+
+#![no_std]
+#![no_main]
+
+use core::{
+    future::Future,
+    pin::Pin,
+    ptr,
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
+use cortex_m::asm;
+use cortex_m_rt::entry;
+use cortex_m_semihosting::hprintln;
+use dma_poc::{Dma, Transfer};
+
+const SRC: &[u8; 16] = b"THIS IS DMADATA!";
+static mut DST: [u8; 16] = [0; 16];
+
+#[entry]
+fn main() -> ! {
+    let dst = unsafe { &mut DST };
+
+    let (_dma, src, dst) = block_on(async {
+        Transfer::start_on(Dma::mem2mem(), SRC, dst)
+            .await
+            .expect("Transfer error")
+    });
+
+    assert_eq!(src, dst);
+
+    hprintln!("Transfer finished successfully").unwrap();
+    loop {
+        continue;
+    }
+}
+
+fn block_on<F: Future>(mut future: F) -> F::Output {
+    // NOTE(unsafe) `future` is never moved after being pinned here
+    let mut future = unsafe { Pin::new_unchecked(&mut future) };
+
+    let waker = unsafe { Waker::from_raw(noop_raw_waker()) };
+    let mut cx = Context::from_waker(&waker);
+
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        asm::wfi();
+    }
+}
+
+fn noop_raw_waker() -> RawWaker {
+    fn clone(_: *const ()) -> RawWaker {
+        noop_raw_waker()
+    }
+    fn noop(_: *const ()) {}
+
+    static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+    RawWaker::new(ptr::null(), &VTABLE)
+}
@@ -0,0 +1,36 @@
+//! This example demonstrates that buffers the DMA can't access are
+//! rejected.
+//!
+//! The destination lies in the CCM RAM, which is only connected to the CPU.
+//! Instead of starting a transfer that can never succeed, `try_start_on`
+//! reports the inaccessible buffer.
+
+#![no_std]
+#![no_main]
+
+use cortex_m_rt::entry;
+use cortex_m_semihosting::hprintln;
+use dma_poc::{DmaExt, ErrorReason, Transfer};
+use stm32f3::stm32f303 as pac;
+
+const SRC: &[u8; 16] = b"THIS IS DMADATA!";
+
+// Start of the CCM RAM, which is otherwise unused by this example.
+const CCM_RAM: usize = 0x1000_0000;
+
+#[entry]
+fn main() -> ! {
+    let mut device = pac::Peripherals::take().unwrap();
+    let channels = device.DMA1.split(&mut device.RCC);
+
+    let dst = unsafe { &mut *(CCM_RAM as *mut [u8; 16]) };
+    let error = Transfer::try_start_on(channels.ch1, SRC, dst)
+        .err()
+        .expect("Transfer started on CCM RAM");
+    assert_eq!(error.reason, ErrorReason::InaccessibleMemory);
+
+    hprintln!("CCM RAM buffer rejected").unwrap();
+    loop {
+        continue;
+    }
+}
@@ -0,0 +1,35 @@
+//! This example demonstrates reusing a DMA channel for consecutive
+//! transfers, in an application that owns its device peripherals.
+
+#![no_std]
+#![no_main]
+
+use cortex_m_rt::entry;
+use cortex_m_semihosting::hprintln;
+use dma_poc::{DmaExt, Transfer};
+use stm32f3::stm32f303 as pac;
+
+const SRC1: &[u8; 16] = b"THIS IS DMADATA!";
+const SRC2: &[u8; 16] = b"AND THIS IS MORE";
+static mut DST: [u8; 16] = [0; 16];
+
+#[entry]
+fn main() -> ! {
+    let mut device = pac::Peripherals::take().unwrap();
+    let channels = device.DMA1.split(&mut device.RCC);
+
+    let dst = unsafe { &mut DST };
+    let transfer = Transfer::start_on(channels.ch4, SRC1, dst);
+    let (ch4, src, dst) = transfer.wait().expect("Transfer error");
+    assert_eq!(src, dst);
+
+    // The channel handed back by `wait` serves the next transfer.
+    let transfer = Transfer::start_on(ch4, SRC2, dst);
+    let (_ch4, src, dst) = transfer.wait().expect("Transfer error");
+    assert_eq!(src, dst);
+
+    hprintln!("Transfers finished successfully").unwrap();
+    loop {
+        continue;
+    }
+}
@@ -0,0 +1,57 @@
+//! This example demonstrates a transfer longer than the 65535 data items
+//! the DMA can move at once.
+//!
+//! It sends 100000 bytes from flash through SPI1. The transfer is split
+//! into chunks behind the scenes. Each chunk is armed from the interrupt
+//! handler as soon as the previous one completes, even though no interrupts
+//! are enabled in the configuration.
+
+#![no_std]
+#![no_main]
+
+use cortex_m_rt::entry;
+use cortex_m_semihosting::hprintln;
+use dma_poc::{Direction, DmaExt, Transfer, TransferConfig, Tx};
+use stm32f3::stm32f303 as pac;
+
+static SRC: [u8; 100_000] = [0x55; 100_000];
+
+#[entry]
+fn main() -> ! {
+    let mut device = pac::Peripherals::take().unwrap();
+    let channels = device.DMA1.split(&mut device.RCC);
+
+    setup_spi1(&device.RCC, &device.SPI1);
+    let tx = Tx::new(device.SPI1);
+
+    let config = TransferConfig::builder()
+        .direction(Direction::MemoryToPeripheral)
+        .peripheral_increment(false)
+        .build()
+        .unwrap();
+
+    // SPI1_TX is served by DMA1 channel 3.
+    let transfer = Transfer::to_peripheral_with(channels.ch3, config, &SRC[..], tx);
+    assert_eq!(transfer.remaining(), SRC.len());
+
+    let (_ch3, _src, _tx) = transfer.wait().expect("Transfer error");
+
+    hprintln!("Transfer finished successfully").unwrap();
+    loop {
+        continue;
+    }
+}
+
+fn setup_spi1(rcc: &pac::RCC, spi: &pac::SPI1) {
+    rcc.apb2enr.modify(|_, w| w.spi1en().enabled());
+
+    // Master with software slave management, so no pins are needed to
+    // shift out the data.
+    spi.cr1.write(|w| {
+        w.mstr().master();
+        w.ssm().enabled();
+        w.ssi().slave_not_selected();
+        w.br().div2();
+        w.spe().enabled()
+    });
+}
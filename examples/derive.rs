@@ -0,0 +1,43 @@
+//! This example demonstrates transfers of user-defined word types.
+//!
+//! Requires the `derive` feature: `cargo run --example derive --features derive`
+
+#![no_std]
+#![no_main]
+
+use cortex_m_rt::entry;
+use cortex_m_semihosting::hprintln;
+use dma_poc::{DmaExt, DmaWord, Transfer};
+use stm32f3::stm32f303 as pac;
+
+/// A signed ADC sample.
+#[derive(Clone, Copy, Debug, PartialEq, DmaWord)]
+#[repr(transparent)]
+struct Sample(i16);
+
+const SAMPLES: &[Sample; 4] = &[Sample(-2048), Sample(-1), Sample(0), Sample(2047)];
+const READINGS: &[f32; 4] = &[-1.5, 0.0, 0.25, 1e6];
+
+static mut SAMPLES_DST: [Sample; 4] = [Sample(0); 4];
+static mut READINGS_DST: [f32; 4] = [0.0; 4];
+
+#[entry]
+fn main() -> ! {
+    let mut device = pac::Peripherals::take().unwrap();
+    let channels = device.DMA1.split(&mut device.RCC);
+
+    let dst = unsafe { &mut SAMPLES_DST };
+    let transfer = Transfer::start_on(channels.ch1, SAMPLES, dst);
+    let (ch1, src, dst) = transfer.wait().expect("Transfer error");
+    assert_eq!(src, dst);
+
+    let dst = unsafe { &mut READINGS_DST };
+    let transfer = Transfer::start_on(ch1, READINGS, dst);
+    let (_ch1, src, dst) = transfer.wait().expect("Transfer error");
+    assert_eq!(src, dst);
+
+    hprintln!("Transfers finished successfully").unwrap();
+    loop {
+        continue;
+    }
+}
@@ -0,0 +1,26 @@
+//! This example demonstrates placing a static DMA buffer in the
+//! `.dma_buffers` section, which is guaranteed to be DMA-accessible.
+
+#![no_std]
+#![no_main]
+
+use cortex_m_rt::entry;
+use cortex_m_semihosting::hprintln;
+use dma_poc::{dma_buffer, Transfer};
+
+const SRC: &[u8; 16] = b"THIS IS DMADATA!";
+
+#[entry]
+fn main() -> ! {
+    let dst: &'static mut [u8; 16] = dma_buffer!(: [u8; 16] = [0; 16]).unwrap();
+    hprintln!("Buffer at {:p}", dst).unwrap();
+
+    let transfer = Transfer::start(SRC, dst);
+    let (_dma, src, dst) = transfer.wait().expect("Transfer error");
+    assert_eq!(src, dst);
+
+    hprintln!("Transfer finished successfully").unwrap();
+    loop {
+        continue;
+    }
+}
@@ -0,0 +1,39 @@
+//! This example demonstrates waiting for a DMA transfer without busy-waiting.
+//!
+//! With the completion interrupts enabled, `Transfer::wait` sleeps until
+//! the crate-provided interrupt handler signals the end of the transfer.
+
+#![no_std]
+#![no_main]
+
+use cortex_m_rt::entry;
+use cortex_m_semihosting::hprintln;
+use dma_poc::{Dma, Transfer, TransferConfig};
+
+const SRC: &[u8; 16] = b"THIS IS DMADATA!";
+static mut DST: [u8; 16] = [0; 16];
+
+#[entry]
+fn main() -> ! {
+    let transfer = start();
+    let (_dma, src, dst) = transfer.wait().expect("Transfer error");
+
+    assert_eq!(src, dst);
+
+    hprintln!("Transfer finished successfully").unwrap();
+    loop {
+        continue;
+    }
+}
+
+#[inline(never)]
+fn start() -> Transfer<&'static [u8; 16], &'static mut [u8; 16]> {
+    let config = TransferConfig::builder()
+        .transfer_complete_interrupt(true)
+        .transfer_error_interrupt(true)
+        .build()
+        .unwrap();
+
+    let dst = unsafe { &mut DST };
+    Transfer::start_with(Dma::mem2mem(), config, SRC, dst)
+}
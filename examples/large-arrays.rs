@@ -0,0 +1,47 @@
+//! This example demonstrates transfers on large and nested arrays.
+
+#![no_std]
+#![no_main]
+
+use cortex_m_rt::entry;
+use cortex_m_semihosting::hprintln;
+use dma_poc::{DmaExt, Transfer};
+use stm32f3::stm32f303 as pac;
+
+static BYTES: [u8; 256] = [0xa5; 256];
+static WORDS: [u16; 1024] = [0xbeef; 1024];
+static SAMPLES: [[u16; 2]; 512] = [[0x1234, 0x5678]; 512];
+
+static mut BYTES_DST: [u8; 256] = [0; 256];
+static mut WORDS_DST: [u16; 1024] = [0; 1024];
+static mut SAMPLES_DST: [[u16; 2]; 512] = [[0; 2]; 512];
+
+#[entry]
+fn main() -> ! {
+    let mut device = pac::Peripherals::take().unwrap();
+    let channels = device.DMA1.split(&mut device.RCC);
+
+    let dst = unsafe { &mut BYTES_DST };
+    let transfer = Transfer::start_on(channels.ch1, &BYTES, dst);
+    assert_eq!(transfer.remaining(), 256);
+    let (ch1, src, dst) = transfer.wait().expect("Transfer error");
+    assert_eq!(src, dst);
+
+    let dst = unsafe { &mut WORDS_DST };
+    let transfer = Transfer::start_on(ch1, &WORDS, dst);
+    assert_eq!(transfer.remaining(), 1024);
+    let (ch1, src, dst) = transfer.wait().expect("Transfer error");
+    assert_eq!(src, dst);
+
+    // Nested arrays are transferred as a flat sequence of words.
+    let dst = unsafe { &mut SAMPLES_DST };
+    let transfer = Transfer::start_on(ch1, &SAMPLES, dst);
+    assert_eq!(transfer.remaining(), 1024);
+    let (_ch1, src, dst) = transfer.wait().expect("Transfer error");
+    assert_eq!(src, dst);
+
+    hprintln!("Transfers finished successfully").unwrap();
+    loop {
+        continue;
+    }
+}
@@ -0,0 +1,36 @@
+//! This example demonstrates a DMA transfer between buffers of different
+//! word sizes, letting the DMA do the width conversion.
+
+#![no_std]
+#![no_main]
+
+use cortex_m_rt::entry;
+use cortex_m_semihosting::hprintln;
+use dma_poc::Transfer;
+
+const SRC: &[u8; 4] = b"DMA!";
+static mut DST: [u32; 4] = [0; 4];
+
+#[entry]
+fn main() -> ! {
+    let transfer = start();
+    let (_dma, src, dst) = transfer.wait().expect("Transfer error");
+
+    // Every byte was zero-extended into a full word.
+    for (s, d) in src.iter().zip(dst.iter()) {
+        assert_eq!(u32::from(*s), *d);
+    }
+
+    hprintln!("Transfer finished successfully").unwrap();
+    loop {
+        continue;
+    }
+}
+
+#[inline(never)]
+fn start() -> Transfer<&'static [u8], &'static mut [u32]> {
+    let dst = unsafe { &mut DST };
+
+    // `Transfer::start` would reject this, since the word types differ.
+    Transfer::start_packing(SRC, dst)
+}
@@ -0,0 +1,55 @@
+//! This example demonstrates double-buffered reception from USART1, using
+//! a `PingPong` transfer.
+//!
+//! The USART pins (PC4/PC5) need to be connected to some sender that
+//! transmits at 9600 baud.
+
+#![no_std]
+#![no_main]
+
+use cortex_m_rt::entry;
+use cortex_m_semihosting::hprintln;
+use dma_poc::{DmaExt, PingPong, PingPongError, Rx};
+use stm32f3::stm32f303 as pac;
+
+static mut BUF: [u8; 32] = [0; 32];
+
+#[entry]
+fn main() -> ! {
+    let mut device = pac::Peripherals::take().unwrap();
+    let channels = device.DMA1.split(&mut device.RCC);
+
+    setup_usart1(&device.RCC, &device.GPIOC, &device.USART1);
+    let rx = Rx::new(device.USART1);
+
+    let buf = unsafe { &mut BUF };
+    let mut pingpong = PingPong::start(channels.ch5, rx, buf);
+    let mut half = [0; 16];
+
+    loop {
+        // While we copy out one half, the DMA keeps filling the other one.
+        match pingpong.read(&mut half) {
+            Ok(n) => {
+                let lines = half[..n].iter().filter(|&&b| b == b'\n').count();
+                hprintln!("{} line(s) received", lines).unwrap();
+            }
+            Err(PingPongError::Overrun) => hprintln!("overrun, data lost").unwrap(),
+            Err(PingPongError::BusError) => panic!("transfer error"),
+        }
+    }
+}
+
+fn setup_usart1(rcc: &pac::RCC, gpioc: &pac::GPIOC, usart: &pac::USART1) {
+    rcc.ahbenr.modify(|_, w| w.iopcen().enabled());
+    rcc.apb2enr.modify(|_, w| w.usart1en().enabled());
+
+    // PC4 (TX) and PC5 (RX) in alternate function 7
+    gpioc
+        .moder
+        .modify(|_, w| w.moder4().alternate().moder5().alternate());
+    gpioc.afrl.modify(|_, w| w.afrl4().af7().afrl5().af7());
+
+    // 9600 baud at the default 8 MHz clock
+    usart.brr.write(|w| w.brr().bits((8_000_000 / 9600) as u16));
+    usart.cr1.modify(|_, w| w.re().enabled().ue().enabled());
+}
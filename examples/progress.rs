@@ -0,0 +1,36 @@
+//! This example demonstrates reporting the progress of a running transfer.
+
+#![no_std]
+#![no_main]
+
+use cortex_m_rt::entry;
+use cortex_m_semihosting::hprintln;
+use dma_poc::Transfer;
+
+static SRC: [u32; 32] = [0xdead_beef; 32];
+static mut DST: [u32; 32] = [0; 32];
+
+#[entry]
+fn main() -> ! {
+    let dst = unsafe { &mut DST };
+    let transfer = Transfer::start(&SRC, dst);
+
+    // Progress is counted in words, so it never exceeds the buffer length,
+    // even though each word is 4 bytes.
+    let mut last = 0;
+    while !transfer.is_done() {
+        let transferred = transfer.transferred();
+        assert!(transferred >= last && transferred <= SRC.len());
+        // the DMA may have moved on since `transferred` was read
+        assert!(transferred + transfer.remaining() <= SRC.len());
+        last = transferred;
+    }
+
+    let (_dma, src, dst) = transfer.wait().expect("Transfer error");
+    assert_eq!(src, dst);
+
+    hprintln!("Transfer finished successfully").unwrap();
+    loop {
+        continue;
+    }
+}
@@ -0,0 +1,49 @@
+//! This example demonstrates serving several transfers from a main loop,
+//! using `Transfer::poll` instead of blocking in `Transfer::wait`.
+
+#![no_std]
+#![no_main]
+
+use core::task::Poll;
+use cortex_m_rt::entry;
+use cortex_m_semihosting::hprintln;
+use dma_poc::{ChannelId, DmaExt, Transfer};
+use stm32f3::stm32f303 as pac;
+
+const SRC1: &[u8; 16] = b"THIS IS DMADATA!";
+const SRC2: &[u8; 16] = b"AND THIS IS MORE";
+static mut DST1: [u8; 16] = [0; 16];
+static mut DST2: [u8; 16] = [0; 16];
+
+#[entry]
+fn main() -> ! {
+    let mut device = pac::Peripherals::take().unwrap();
+    let channels = device.DMA1.split(&mut device.RCC);
+
+    let dst1 = unsafe { &mut DST1 };
+    let dst2 = unsafe { &mut DST2 };
+    let mut transfer1 = Some(Transfer::start_on(channels.ch1, SRC1, dst1));
+    let mut transfer2 = Some(Transfer::start_on(channels.ch2, SRC2, dst2));
+
+    while transfer1.is_some() || transfer2.is_some() {
+        serve(&mut transfer1);
+        serve(&mut transfer2);
+
+        // other work of the main loop goes here
+    }
+
+    hprintln!("Transfers finished successfully").unwrap();
+    loop {
+        continue;
+    }
+}
+
+fn serve<C: ChannelId>(transfer: &mut Option<Transfer<&[u8; 16], &mut [u8; 16], C>>) {
+    if let Some(t) = transfer {
+        if let Poll::Ready(result) = t.poll() {
+            let (_dma, src, dst) = result.expect("Transfer error");
+            assert_eq!(src, dst);
+            *transfer = None;
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! This example demonstrates waiting for a transfer with a timeout.
+//!
+//! It tries to receive 16 bytes from USART1, but gives up after one second
+//! (at the default 8 MHz clock). Unless a sender is connected to the USART
+//! pins (PC4/PC5), the transfer times out.
+
+#![no_std]
+#![no_main]
+
+use cortex_m_rt::entry;
+use cortex_m_semihosting::hprintln;
+use dma_poc::{DmaExt, ErrorReason, Rx, Transfer, TransferError};
+use stm32f3::stm32f303 as pac;
+
+const TIMEOUT: u32 = 8_000_000;
+
+static mut DST: [u8; 16] = [0; 16];
+
+#[entry]
+fn main() -> ! {
+    let mut core = cortex_m::Peripherals::take().unwrap();
+    let mut device = pac::Peripherals::take().unwrap();
+    let channels = device.DMA1.split(&mut device.RCC);
+
+    core.DWT.enable_cycle_counter();
+
+    setup_usart1(&device.RCC, &device.GPIOC, &device.USART1);
+    let rx = Rx::new(device.USART1);
+
+    let dst = unsafe { &mut DST };
+    let transfer = Transfer::from_peripheral(channels.ch5, rx, dst);
+
+    match transfer.wait_timeout(&mut core.DWT, TIMEOUT) {
+        Ok((_ch5, _rx, dst)) => hprintln!("Received: {:?}", dst).unwrap(),
+        Err(TransferError {
+            reason: ErrorReason::Timeout,
+            transferred,
+            dst,
+            ..
+        }) => {
+            hprintln!("Timed out, received: {:?}", &dst[..transferred]).unwrap();
+        }
+        Err(error) => panic!("{:?}", error),
+    }
+
+    loop {
+        continue;
+    }
+}
+
+fn setup_usart1(rcc: &pac::RCC, gpioc: &pac::GPIOC, usart: &pac::USART1) {
+    rcc.ahbenr.modify(|_, w| w.iopcen().enabled());
+    rcc.apb2enr.modify(|_, w| w.usart1en().enabled());
+
+    // PC4 (TX) and PC5 (RX) in alternate function 7
+    gpioc
+        .moder
+        .modify(|_, w| w.moder4().alternate().moder5().alternate());
+    gpioc.afrl.modify(|_, w| w.afrl4().af7().afrl5().af7());
+
+    // 9600 baud at the default 8 MHz clock
+    usart.brr.write(|w| w.brr().bits((8_000_000 / 9600) as u16));
+    usart.cr1.modify(|_, w| w.re().enabled().ue().enabled());
+}
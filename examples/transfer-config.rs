@@ -0,0 +1,43 @@
+//! This example demonstrates starting a DMA transfer with a custom channel
+//! configuration.
+
+#![no_std]
+#![no_main]
+
+use cortex_m_rt::entry;
+use cortex_m_semihosting::hprintln;
+use dma_poc::{ConfigError, Direction, Dma, Priority, Transfer, TransferConfig};
+
+const SRC: &[u8; 16] = b"THIS IS DMADATA!";
+static mut DST: [u8; 16] = [0; 16];
+
+#[entry]
+fn main() -> ! {
+    // Invalid combinations are rejected instead of being programmed.
+    let invalid = TransferConfig::builder()
+        .direction(Direction::MemoryToMemory)
+        .circular(true)
+        .build();
+    assert_eq!(invalid, Err(ConfigError::CircularMemToMem));
+
+    let transfer = start();
+    let (_dma, src, dst) = transfer.wait().expect("Transfer error");
+
+    assert_eq!(src, dst);
+
+    hprintln!("Transfer finished successfully").unwrap();
+    loop {
+        continue;
+    }
+}
+
+#[inline(never)]
+fn start() -> Transfer<&'static [u8; 16], &'static mut [u8; 16]> {
+    let config = TransferConfig::builder()
+        .priority(Priority::VeryHigh)
+        .build()
+        .unwrap();
+
+    let dst = unsafe { &mut DST };
+    Transfer::start_with(Dma::mem2mem(), config, SRC, dst)
+}
@@ -0,0 +1,49 @@
+//! This example demonstrates recovering from invalid buffer lengths.
+//!
+//! The length of the data to copy comes from an untrusted source, like the
+//! header of a received packet. Instead of panicking, `try_start_on` hands
+//! back the buffers, so the data can be truncated to fit.
+
+#![no_std]
+#![no_main]
+
+use cortex_m_rt::entry;
+use cortex_m_semihosting::hprintln;
+use dma_poc::{DmaExt, ErrorReason, Transfer, TransferError};
+use stm32f3::stm32f303 as pac;
+
+const PACKET: &[u8; 32] = b"THIS IS DMADATA! AND THIS MORE..";
+static mut DST: [u8; 16] = [0; 16];
+
+#[entry]
+fn main() -> ! {
+    let mut device = pac::Peripherals::take().unwrap();
+    let channels = device.DMA1.split(&mut device.RCC);
+
+    // pretend this was read from the packet header
+    let len = core::hint::black_box(20);
+
+    let dst = unsafe { &mut DST };
+    let transfer = match Transfer::try_start_on(channels.ch1, &PACKET[..len], dst) {
+        Ok(transfer) => transfer,
+        Err(TransferError {
+            dma,
+            src,
+            dst,
+            reason: ErrorReason::LengthMismatch,
+            ..
+        }) => {
+            hprintln!("Packet too long, truncating").unwrap();
+            Transfer::start_on(dma, &src[..dst.len()], dst)
+        }
+        Err(error) => panic!("{:?}", error),
+    };
+
+    let (_dma, src, dst) = transfer.wait().expect("Transfer error");
+    assert_eq!(src, &dst[..]);
+
+    hprintln!("Transfer finished successfully").unwrap();
+    loop {
+        continue;
+    }
+}
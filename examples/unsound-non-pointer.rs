@@ -25,12 +25,17 @@ impl<B> Transfer<B> {
         let slice = dst.as_slice();
 
         let mut dma = Dma::mem2mem();
-        dma.set_paddr(src.as_ptr() as u32);
-        dma.set_maddr(slice.as_ptr() as u32);
-        dma.set_ndt(slice.len() as u16);
+        // NOTE(unsafe) this assumes that `dst` stays where it is during the
+        // transfer, which is wrong for non-pointer buffers
+        unsafe {
+            dma.set_paddr(src.as_ptr() as u32);
+            dma.set_maddr(slice.as_ptr() as u32);
+            dma.set_ndt(slice.len() as u16);
+        }
 
         atomic::compiler_fence(Ordering::Release);
-        dma.enable();
+        // NOTE(unsafe) see above
+        unsafe { dma.enable() };
 
         Transfer { dma, buffer: dst }
     }
@@ -32,12 +32,17 @@ impl<B> Transfer<B> {
         let slice = dst.as_slice();
 
         let mut dma = Dma::mem2mem();
-        dma.set_paddr(src.as_ptr() as u32);
-        dma.set_maddr(slice.as_ptr() as u32);
-        dma.set_ndt(slice.len() as u16);
+        // NOTE(unsafe) this assumes that the pinned buffer stays valid during
+        // the transfer, which `Pin` doesn't guarantee
+        unsafe {
+            dma.set_paddr(src.as_ptr() as u32);
+            dma.set_maddr(slice.as_ptr() as u32);
+            dma.set_ndt(slice.len() as u16);
+        }
 
         atomic::compiler_fence(Ordering::Release);
-        dma.enable();
+        // NOTE(unsafe) see above
+        unsafe { dma.enable() };
 
         Transfer { dma, buffer: dst }
     }
@@ -0,0 +1,54 @@
+//! This example demonstrates continuous reception from USART1 into a
+//! static ring buffer, using a circular DMA transfer.
+//!
+//! The USART pins (PC4/PC5) need to be connected to some sender that
+//! transmits at 9600 baud.
+
+#![no_std]
+#![no_main]
+
+use cortex_m_rt::entry;
+use cortex_m_semihosting::hprintln;
+use dma_poc::{CircularTransfer, DmaExt, Rx};
+use stm32f3::stm32f303 as pac;
+
+static mut RING: [u8; 32] = [0; 32];
+
+#[entry]
+fn main() -> ! {
+    let mut device = pac::Peripherals::take().unwrap();
+    let channels = device.DMA1.split(&mut device.RCC);
+
+    setup_usart1(&device.RCC, &device.GPIOC, &device.USART1);
+    let rx = Rx::new(device.USART1);
+
+    let ring = unsafe { &mut RING };
+    let transfer = CircularTransfer::start(channels.ch5, rx, ring);
+
+    // Wait until the DMA wrote the first half of the ring.
+    while transfer.position() < 16 {
+        continue;
+    }
+
+    let (_ch5, _rx, ring) = transfer.stop();
+    hprintln!("Received: {:?}", &ring[..16]).unwrap();
+
+    loop {
+        continue;
+    }
+}
+
+fn setup_usart1(rcc: &pac::RCC, gpioc: &pac::GPIOC, usart: &pac::USART1) {
+    rcc.ahbenr.modify(|_, w| w.iopcen().enabled());
+    rcc.apb2enr.modify(|_, w| w.usart1en().enabled());
+
+    // PC4 (TX) and PC5 (RX) in alternate function 7
+    gpioc
+        .moder
+        .modify(|_, w| w.moder4().alternate().moder5().alternate());
+    gpioc.afrl.modify(|_, w| w.afrl4().af7().afrl5().af7());
+
+    // 9600 baud at the default 8 MHz clock
+    usart.brr.write(|w| w.brr().bits((8_000_000 / 9600) as u16));
+    usart.cr1.modify(|_, w| w.re().enabled().ue().enabled());
+}
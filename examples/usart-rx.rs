@@ -0,0 +1,52 @@
+//! This example demonstrates receiving data from USART1 into a static
+//! buffer via DMA.
+//!
+//! The USART pins (PC4/PC5) need to be connected to some sender that
+//! transmits at least 16 bytes at 9600 baud.
+
+#![no_std]
+#![no_main]
+
+use cortex_m_rt::entry;
+use cortex_m_semihosting::hprintln;
+use dma_poc::{DmaExt, Rx, Transfer};
+use stm32f3::stm32f303 as pac;
+
+static mut DST: [u8; 16] = [0; 16];
+
+#[entry]
+fn main() -> ! {
+    let mut device = pac::Peripherals::take().unwrap();
+    let channels = device.DMA1.split(&mut device.RCC);
+
+    setup_usart1(&device.RCC, &device.GPIOC, &device.USART1);
+    let rx = Rx::new(device.USART1);
+
+    // USART1_RX is served by DMA1 channel 5.
+    let dst = unsafe { &mut DST };
+    let transfer = Transfer::from_peripheral(channels.ch5, rx, dst);
+    let (_ch5, rx, dst) = transfer.wait().expect("Transfer error");
+
+    // Give the USART back for use without DMA.
+    let _usart1 = rx.free();
+
+    hprintln!("Received: {:?}", dst).unwrap();
+    loop {
+        continue;
+    }
+}
+
+fn setup_usart1(rcc: &pac::RCC, gpioc: &pac::GPIOC, usart: &pac::USART1) {
+    rcc.ahbenr.modify(|_, w| w.iopcen().enabled());
+    rcc.apb2enr.modify(|_, w| w.usart1en().enabled());
+
+    // PC4 (TX) and PC5 (RX) in alternate function 7
+    gpioc
+        .moder
+        .modify(|_, w| w.moder4().alternate().moder5().alternate());
+    gpioc.afrl.modify(|_, w| w.afrl4().af7().afrl5().af7());
+
+    // 9600 baud at the default 8 MHz clock
+    usart.brr.write(|w| w.brr().bits((8_000_000 / 9600) as u16));
+    usart.cr1.modify(|_, w| w.re().enabled().ue().enabled());
+}
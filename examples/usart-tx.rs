@@ -0,0 +1,45 @@
+//! This example demonstrates transmitting a static buffer via USART1
+//! using DMA.
+
+#![no_std]
+#![no_main]
+
+use cortex_m_rt::entry;
+use cortex_m_semihosting::hprintln;
+use dma_poc::{DmaExt, Transfer, Tx};
+use stm32f3::stm32f303 as pac;
+
+const SRC: &[u8; 16] = b"THIS IS DMADATA!";
+
+#[entry]
+fn main() -> ! {
+    let mut device = pac::Peripherals::take().unwrap();
+    let channels = device.DMA1.split(&mut device.RCC);
+
+    setup_usart1(&device.RCC, &device.GPIOC, &device.USART1);
+    let tx = Tx::new(device.USART1);
+
+    // USART1_TX is served by DMA1 channel 4.
+    let transfer = Transfer::to_peripheral(channels.ch4, SRC, tx);
+    let (_ch4, _src, _tx) = transfer.wait().expect("Transfer error");
+
+    hprintln!("Transfer finished successfully").unwrap();
+    loop {
+        continue;
+    }
+}
+
+fn setup_usart1(rcc: &pac::RCC, gpioc: &pac::GPIOC, usart: &pac::USART1) {
+    rcc.ahbenr.modify(|_, w| w.iopcen().enabled());
+    rcc.apb2enr.modify(|_, w| w.usart1en().enabled());
+
+    // PC4 (TX) and PC5 (RX) in alternate function 7
+    gpioc
+        .moder
+        .modify(|_, w| w.moder4().alternate().moder5().alternate());
+    gpioc.afrl.modify(|_, w| w.afrl4().af7().afrl5().af7());
+
+    // 9600 baud at the default 8 MHz clock
+    usart.brr.write(|w| w.brr().bits((8_000_000 / 9600) as u16));
+    usart.cr1.modify(|_, w| w.te().enabled().ue().enabled());
+}
@@ -0,0 +1,32 @@
+//! This example demonstrates performing a DMA read with 32-bit words.
+
+#![no_std]
+#![no_main]
+
+use cortex_m_rt::entry;
+use cortex_m_semihosting::hprintln;
+use dma_poc::Transfer;
+
+const SRC: &[u32; 4] = &[0xdead_beef, 0x0123_4567, 0x89ab_cdef, 0xcafe_babe];
+static mut DST: [u32; 4] = [0; 4];
+
+#[entry]
+fn main() -> ! {
+    let transfer = start();
+    let (_dma, src, dst) = transfer.wait().expect("Transfer error");
+
+    // If the DMA moved bytes instead of words, only the first word would
+    // have been copied.
+    assert_eq!(src, dst);
+
+    hprintln!("Transfer finished successfully").unwrap();
+    loop {
+        continue;
+    }
+}
+
+#[inline(never)]
+fn start() -> Transfer<&'static [u32], &'static mut [u32]> {
+    let dst = unsafe { &mut DST };
+    Transfer::start(SRC, dst)
+}
@@ -7,3 +7,14 @@ MEMORY
 }
 
 _stack_start = ORIGIN(RAM) + LENGTH(RAM);
+
+SECTIONS
+{
+  /* Static DMA buffers, see the `dma_buffer!` macro. Kept in RAM, since the
+     DMA can't access CCRAM. They are initialized at runtime by the macro. */
+  .dma_buffers (NOLOAD) : ALIGN(4)
+  {
+    *(.dma_buffers .dma_buffers.*);
+    . = ALIGN(4);
+  } > RAM
+} INSERT AFTER .bss;
@@ -0,0 +1,39 @@
+//! Static buffers placed in DMA-accessible memory.
+//!
+//! `memory.x` provides a `.dma_buffers` section in RAM. Statics placed
+//! there via `dma_buffer!` can never end up in the CCM RAM, which the DMA
+//! can't access.
+
+/// Create a static buffer in the `.dma_buffers` linker section.
+///
+/// The buffer is initialized to the given value and returned as a
+/// `&'static mut`, which can be used as the destination of a transfer.
+/// Like `cortex_m::singleton!`, this returns `None` if the same invocation
+/// is evaluated more than once, since that would alias the buffer.
+///
+/// ```ignore
+/// let dst: &'static mut [u8; 16] = dma_buffer!(: [u8; 16] = [0; 16]).unwrap();
+/// ```
+#[macro_export]
+macro_rules! dma_buffer {
+    (: $ty:ty = $init:expr) => {{
+        #[link_section = ".dma_buffers"]
+        static mut BUFFER: core::mem::MaybeUninit<$ty> = core::mem::MaybeUninit::uninit();
+        static TAKEN: core::sync::atomic::AtomicBool = core::sync::atomic::AtomicBool::new(false);
+
+        if TAKEN.swap(true, core::sync::atomic::Ordering::AcqRel) {
+            None
+        } else {
+            let init: $ty = $init;
+            // NOTE(unsafe) `TAKEN` makes sure this is the only reference to
+            // `BUFFER`. The section is not initialized by the runtime, so we
+            // write the initial value before handing out the reference.
+            #[allow(unused_unsafe)]
+            unsafe {
+                let ptr = core::ptr::addr_of_mut!(BUFFER) as *mut $ty;
+                ptr.write(init);
+                Some(&mut *ptr)
+            }
+        }
+    }};
+}
@@ -0,0 +1,433 @@
+//! Typed, independently owned DMA channels.
+//!
+//! The DMA controllers are split into their channels via `DmaExt::split`.
+//! Each channel is represented by a `Channel<C>`, where `C` is a marker
+//! type from the `dma1` or `dma2` module identifying the channel.
+
+use crate::{critical_section, pac, Direction, Priority, TransferConfig, WordSize};
+use core::marker::PhantomData;
+use cortex_m::peripheral::NVIC;
+
+/// Total number of DMA channels on the device.
+pub(crate) const CHANNELS: usize = 12;
+
+/// Trait for marker types identifying a single DMA channel.
+///
+/// # Safety
+///
+/// - `registers` must return the register block of the DMA controller the
+///   channel belongs to.
+/// - `NUMBER` must be a valid channel number of that controller.
+/// - `INDEX` must be less than 12, the total number of DMA channels.
+/// - `INTERRUPT` must be the interrupt of that channel.
+/// - Every implementing type must identify a different channel, since
+///   `Channel` relies on being the only owner of its registers. In
+///   particular, no two implementing types may have the same `INDEX`.
+///
+/// Besides the marker types in `dma1` and `dma2`, this can be implemented
+/// by mock channels for testing on the host. Such an implementation points
+/// `registers` to a register block in RAM and overrides `unmask_interrupt`.
+pub unsafe trait ChannelId {
+    /// Channel number within its DMA controller, starting at 1.
+    const NUMBER: u8;
+
+    /// Index of the channel among all DMA channels of the device, used to
+    /// look up per-channel state.
+    const INDEX: usize;
+
+    /// Interrupt raised by the channel.
+    const INTERRUPT: pac::Interrupt;
+
+    fn registers() -> *const pac::dma1::RegisterBlock;
+
+    /// Unmask the channel's interrupt in the NVIC.
+    fn unmask_interrupt() {
+        // NOTE(unsafe) the crate never relies on the interrupt being masked.
+        // The state the handler shares with the owner of the channel is
+        // guarded by critical sections or atomics instead.
+        unsafe { NVIC::unmask(Self::INTERRUPT) };
+    }
+}
+
+/// A single DMA channel.
+pub struct Channel<C> {
+    _id: PhantomData<C>,
+}
+
+impl<C: ChannelId> Channel<C> {
+    /// Create a `Channel` without owning its DMA controller.
+    ///
+    /// # Safety
+    ///
+    /// There must not be another `Channel` for the same channel.
+    pub unsafe fn steal() -> Self {
+        Self { _id: PhantomData }
+    }
+
+    /// Set the peripheral address of the next transfer.
+    ///
+    /// # Safety
+    ///
+    /// Once the channel is enabled, the DMA accesses `addr`. It must be valid
+    /// for the whole transfer, as described by `enable`.
+    pub unsafe fn set_paddr(&mut self, addr: u32) {
+        self.ch().par.write(|w| w.pa().bits(addr));
+    }
+
+    /// Set the memory address of the next transfer.
+    ///
+    /// # Safety
+    ///
+    /// Once the channel is enabled, the DMA accesses `addr`. It must be valid
+    /// for the whole transfer, as described by `enable`.
+    pub unsafe fn set_maddr(&mut self, addr: u32) {
+        self.ch().mar.write(|w| w.ma().bits(addr));
+    }
+
+    /// Set the size of the data items read from or written to the
+    /// peripheral address.
+    ///
+    /// # Safety
+    ///
+    /// The size determines how many bytes the DMA accesses once the channel
+    /// is enabled, see `enable`.
+    pub unsafe fn set_psize(&mut self, size: WordSize) {
+        self.ch().cr.modify(|_, w| match size {
+            WordSize::Bits8 => w.psize().bits8(),
+            WordSize::Bits16 => w.psize().bits16(),
+            WordSize::Bits32 => w.psize().bits32(),
+        });
+    }
+
+    /// Set the size of the data items read from or written to the memory
+    /// address.
+    ///
+    /// # Safety
+    ///
+    /// The size determines how many bytes the DMA accesses once the channel
+    /// is enabled, see `enable`.
+    pub unsafe fn set_msize(&mut self, size: WordSize) {
+        self.ch().cr.modify(|_, w| match size {
+            WordSize::Bits8 => w.msize().bits8(),
+            WordSize::Bits16 => w.msize().bits16(),
+            WordSize::Bits32 => w.msize().bits32(),
+        });
+    }
+
+    /// Set the number of data items of the next transfer.
+    ///
+    /// # Safety
+    ///
+    /// The length determines how many bytes the DMA accesses once the channel
+    /// is enabled, see `enable`.
+    pub unsafe fn set_ndt(&mut self, len: u16) {
+        self.ch().ndtr.write(|w| w.ndt().bits(len));
+    }
+
+    /// Number of data items left to transfer.
+    pub fn ndt(&self) -> u16 {
+        self.ch().ndtr.read().ndt().bits()
+    }
+
+    /// Start the transfer programmed into the channel.
+    ///
+    /// # Safety
+    ///
+    /// The DMA reads and writes memory behind the back of the compiler. The
+    /// programmed addresses, data sizes and length must describe memory that
+    /// is valid and not otherwise accessed for the whole transfer, i.e. until
+    /// the channel is disabled or the transfer completes.
+    pub unsafe fn enable(&mut self) {
+        // clear interrupt flags
+        self.clear_flag(Flag::Global);
+
+        let cr = self.ch().cr.read();
+        if cr.tcie().is_enabled() || cr.htie().is_enabled() || cr.teie().is_enabled() {
+            C::unmask_interrupt();
+        }
+
+        self.ch().cr.modify(|_, w| w.en().enabled());
+    }
+
+    pub fn disable(&mut self) {
+        self.ch().cr.modify(|_, w| w.en().disabled());
+    }
+
+    pub fn transfer_complete(&self) -> bool {
+        self.flag_set(Flag::TransferComplete)
+    }
+
+    pub fn transfer_error(&self) -> bool {
+        self.flag_set(Flag::TransferError)
+    }
+
+    pub fn half_transfer(&self) -> bool {
+        self.flag_set(Flag::HalfTransfer)
+    }
+
+    pub fn clear_transfer_complete(&mut self) {
+        self.clear_flag(Flag::TransferComplete);
+    }
+
+    pub fn clear_half_transfer(&mut self) {
+        self.clear_flag(Flag::HalfTransfer);
+    }
+
+    /// Configure the channel according to `config`.
+    ///
+    /// This also disables the channel. Data sizes not given in `config`
+    /// are set to 8 bits.
+    pub(crate) fn configure(&mut self, config: &TransferConfig) {
+        self.ch().cr.write(|w| {
+            match config.direction {
+                Direction::PeripheralToMemory => w.dir().from_peripheral().mem2mem().disabled(),
+                Direction::MemoryToPeripheral => w.dir().from_memory().mem2mem().disabled(),
+                Direction::MemoryToMemory => w.dir().from_peripheral().mem2mem().enabled(),
+            };
+            match config.priority {
+                Priority::Low => w.pl().low(),
+                Priority::Medium => w.pl().medium(),
+                Priority::High => w.pl().high(),
+                Priority::VeryHigh => w.pl().very_high(),
+            };
+            w.pinc().bit(config.peripheral_increment);
+            w.minc().bit(config.memory_increment);
+            w.circ().bit(config.circular);
+            w.tcie().bit(config.transfer_complete_interrupt);
+            w.htie().bit(config.half_transfer_interrupt);
+            w.teie().bit(config.transfer_error_interrupt);
+            w.en().disabled()
+        });
+
+        // NOTE(unsafe) the channel was just disabled, and is only enabled
+        // again after the transfer has been programmed
+        unsafe {
+            self.set_psize(config.peripheral_size.unwrap_or(WordSize::Bits8));
+            self.set_msize(config.memory_size.unwrap_or(WordSize::Bits8));
+        }
+        self.clear_chunks();
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.ch().cr.read().en().is_enabled()
+    }
+
+    /// Bytes the peripheral and memory addresses advance per data item.
+    pub(crate) fn address_steps(&self) -> (u32, u32) {
+        let cr = self.ch().cr.read();
+        let pstep = if cr.pinc().bit() {
+            1 << cr.psize().bits()
+        } else {
+            0
+        };
+        let mstep = if cr.minc().bit() {
+            1 << cr.msize().bits()
+        } else {
+            0
+        };
+        (pstep, mstep)
+    }
+
+    /// Whether the end of the current transfer raises an interrupt.
+    ///
+    /// A transfer ends either with the transfer complete or the transfer
+    /// error flag, so both interrupts have to be enabled.
+    pub(crate) fn completion_interrupt_enabled(&self) -> bool {
+        let cr = self.ch().cr.read();
+        cr.tcie().is_enabled() && cr.teie().is_enabled()
+    }
+
+    /// Make the end of the current transfer raise an interrupt, whether it
+    /// completes or fails.
+    pub(crate) fn enable_completion_interrupt(&mut self) {
+        self.ch()
+            .cr
+            .modify(|_, w| w.tcie().enabled().teie().enabled());
+        C::unmask_interrupt();
+    }
+
+    /// Handle an interrupt of this channel.
+    ///
+    /// If a chunk of a long transfer completed, this arms the next chunk.
+    /// Otherwise, it masks the interrupts whose flags are set, but leaves the
+    /// flags themselves alone, so the owner of the channel can still observe
+    /// them.
+    ///
+    /// This is called by the crate-provided interrupt handlers. Applications
+    /// that define their own DMA handlers disable the `interrupt-handlers`
+    /// feature, and must call this from the handler of every channel they
+    /// use. Long transfers rely on it even if no interrupts are configured.
+    pub fn on_interrupt() {
+        // NOTE(unsafe) we only touch interrupt enable bits and flags, and arm
+        // the next chunk of a running transfer, which doesn't interfere with
+        // the owner of the channel.
+        let mut this = unsafe { Self::steal() };
+
+        // Arm the next chunk of a long transfer. The transfer goes on, so
+        // there is nothing to report yet.
+        if critical_section(|cs| this.rearm(cs)) {
+            return;
+        }
+
+        let tc = this.flag_set(Flag::TransferComplete);
+        let ht = this.flag_set(Flag::HalfTransfer);
+        let te = this.flag_set(Flag::TransferError);
+
+        this.ch().cr.modify(|r, w| {
+            w.tcie().bit(r.tcie().bit() && !tc);
+            w.htie().bit(r.htie().bit() && !ht);
+            w.teie().bit(r.teie().bit() && !te)
+        });
+
+        #[cfg(feature = "async")]
+        crate::waker::WAKERS[C::INDEX].wake();
+    }
+
+    fn block(&self) -> &pac::dma1::RegisterBlock {
+        // NOTE(unsafe) `ChannelId` guarantees a valid register block, and
+        // we only access registers belonging to our channel, or write-only
+        // ones in a way that doesn't affect other channels.
+        unsafe { &*C::registers() }
+    }
+
+    fn ch(&self) -> &pac::dma1::CH {
+        let block = self.block();
+        match C::NUMBER {
+            1 => &block.ch1,
+            2 => &block.ch2,
+            3 => &block.ch3,
+            4 => &block.ch4,
+            5 => &block.ch5,
+            6 => &block.ch6,
+            7 => &block.ch7,
+            _ => unreachable!(),
+        }
+    }
+
+    fn flag(flag: Flag) -> u32 {
+        1 << (4 * (u32::from(C::NUMBER) - 1) + flag as u32)
+    }
+
+    fn flag_set(&self, flag: Flag) -> bool {
+        self.block().isr.read().bits() & Self::flag(flag) != 0
+    }
+
+    fn clear_flag(&mut self, flag: Flag) {
+        self.block()
+            .ifcr
+            .write(|w| unsafe { w.bits(Self::flag(flag)) });
+    }
+}
+
+/// Per-channel interrupt flags, as offsets into the ISR/IFCR registers.
+#[derive(Clone, Copy)]
+enum Flag {
+    Global = 0,
+    TransferComplete = 1,
+    HalfTransfer = 2,
+    TransferError = 3,
+}
+
+/// Handle that is able to enable the clocks of the DMA controllers.
+///
+/// This is implemented for `pac::RCC`. Applications that already handed
+/// the RCC to some other owner (e.g. a HAL) can implement it for whatever
+/// handle they have to the AHB clock-enable register.
+pub trait DmaClock {
+    fn enable_dma1(&mut self);
+    fn enable_dma2(&mut self);
+}
+
+impl DmaClock for pac::RCC {
+    fn enable_dma1(&mut self) {
+        self.ahbenr.modify(|_, w| w.dma1en().enabled());
+    }
+
+    fn enable_dma2(&mut self) {
+        self.ahbenr.modify(|_, w| w.dma2en().enabled());
+    }
+}
+
+/// Extension trait to split a DMA controller into its channels.
+pub trait DmaExt {
+    type Channels;
+
+    /// Enable the DMA controller and split it into independent channels.
+    fn split<K: DmaClock>(self, clock: &mut K) -> Self::Channels;
+}
+
+macro_rules! dma {
+    (
+        $DMAX:ident, $dmax:ident, $enable_dmax:ident, $offset:expr,
+        [ $( $CX:ident: ($chx:ident, $x:expr, $INT:ident), )+ ]
+    ) => {
+        /// Channels of the
+        #[doc = stringify!($DMAX)]
+        /// controller.
+        pub mod $dmax {
+            use super::{Channel, ChannelId, DmaClock, DmaExt};
+            use crate::pac;
+
+            $(
+                /// Marker type for channel
+                #[doc = stringify!($x)]
+                pub struct $CX;
+
+                unsafe impl ChannelId for $CX {
+                    const NUMBER: u8 = $x;
+                    const INDEX: usize = $offset + $x - 1;
+                    const INTERRUPT: pac::Interrupt = pac::Interrupt::$INT;
+
+                    fn registers() -> *const pac::dma1::RegisterBlock {
+                        pac::$DMAX::ptr()
+                    }
+                }
+            )+
+
+            /// The independently owned channels of the controller.
+            pub struct Channels {
+                $( pub $chx: Channel<$CX>, )+
+            }
+
+            impl DmaExt for pac::$DMAX {
+                type Channels = Channels;
+
+                fn split<K: DmaClock>(self, clock: &mut K) -> Channels {
+                    clock.$enable_dmax();
+
+                    // NOTE(unsafe) we own the controller, so there are no
+                    // other `Channel`s for it.
+                    unsafe {
+                        Channels {
+                            $( $chx: Channel::steal(), )+
+                        }
+                    }
+                }
+            }
+        }
+    };
+}
+
+dma!(
+    DMA1, dma1, enable_dma1, 0,
+    [
+        C1: (ch1, 1, DMA1_CH1),
+        C2: (ch2, 2, DMA1_CH2),
+        C3: (ch3, 3, DMA1_CH3),
+        C4: (ch4, 4, DMA1_CH4),
+        C5: (ch5, 5, DMA1_CH5),
+        C6: (ch6, 6, DMA1_CH6),
+        C7: (ch7, 7, DMA1_CH7),
+    ]
+);
+
+dma!(
+    DMA2, dma2, enable_dma2, 7,
+    [
+        C1: (ch1, 1, DMA2_CH1),
+        C2: (ch2, 2, DMA2_CH2),
+        C3: (ch3, 3, DMA2_CH3),
+        C4: (ch4, 4, DMA2_CH4),
+        C5: (ch5, 5, DMA2_CH5),
+    ]
+);
@@ -0,0 +1,126 @@
+//! Splitting of one-shot transfers into hardware-sized chunks.
+//!
+//! The DMA moves at most 65535 data items at once, since NDTR is only 16
+//! bits wide. Longer transfers are split into a sequence of chunks. When a
+//! chunk completes, the next one is armed from the channel's interrupt
+//! handler, or by whoever polls the transfer if they notice first. Both
+//! happen inside critical sections, so they can't race. Transfers with more
+//! than one chunk always enable the transfer complete and transfer error
+//! interrupts, so they make progress even if nobody polls them.
+
+use crate::{channel::CHANNELS, critical_section, Channel, ChannelId};
+use core::cell::Cell;
+use cortex_m::interrupt::{CriticalSection, Mutex};
+
+/// Maximum number of data items in a single chunk.
+const MAX_CHUNK: usize = u16::MAX as usize;
+
+/// The part of a transfer that still has to be armed.
+#[derive(Clone, Copy)]
+struct Chunks {
+    /// Number of data items after the current chunk.
+    remaining: usize,
+    /// Peripheral address of the next chunk.
+    paddr: u32,
+    /// Memory address of the next chunk.
+    maddr: u32,
+    /// Bytes the peripheral address advances per data item.
+    pstep: u32,
+    /// Bytes the memory address advances per data item.
+    mstep: u32,
+}
+
+impl Chunks {
+    const NONE: Self = Self {
+        remaining: 0,
+        paddr: 0,
+        maddr: 0,
+        pstep: 0,
+        mstep: 0,
+    };
+}
+
+#[allow(clippy::declare_interior_mutable_const)]
+const EMPTY: Mutex<Cell<Chunks>> = Mutex::new(Cell::new(Chunks::NONE));
+
+/// Pending chunks per DMA channel, indexed by `ChannelId::INDEX`.
+static CHUNKS: [Mutex<Cell<Chunks>>; CHANNELS] = [EMPTY; CHANNELS];
+
+impl<C: ChannelId> Channel<C> {
+    /// Program the addresses and length of a transfer.
+    ///
+    /// If the transfer is too long for a single DMA transfer, only its
+    /// first chunk is programmed, and the rest is remembered for `rearm`.
+    /// The transfer complete and transfer error interrupts are enabled
+    /// then, so the interrupt handler can arm the next chunks, and a failing
+    /// chunk still wakes up `Transfer::wait`. The channel must be disabled and
+    /// configured already, since the address increments are taken from its
+    /// configuration.
+    pub(crate) fn set_transfer(&mut self, paddr: u32, maddr: u32, len: usize) {
+        let (pstep, mstep) = self.address_steps();
+        let chunks = Chunks {
+            remaining: len,
+            paddr,
+            maddr,
+            pstep,
+            mstep,
+        };
+
+        critical_section(|cs| {
+            CHUNKS[C::INDEX].borrow(cs).set(chunks);
+            self.next_chunk(cs);
+        });
+
+        if len > MAX_CHUNK {
+            self.enable_completion_interrupt();
+        }
+    }
+
+    /// Forget any remaining chunks of a previous transfer.
+    pub(crate) fn clear_chunks(&mut self) {
+        critical_section(|cs| CHUNKS[C::INDEX].borrow(cs).set(Chunks::NONE));
+    }
+
+    /// Number of data items left, including those of chunks not yet armed.
+    pub(crate) fn remaining_words(&self) -> usize {
+        critical_section(|cs| usize::from(self.ndt()) + CHUNKS[C::INDEX].borrow(cs).get().remaining)
+    }
+
+    /// Arm the next chunk if the current one has completed.
+    ///
+    /// Returns whether a chunk was armed. Nothing happens if the channel
+    /// was stopped or reported an error.
+    pub(crate) fn rearm(&mut self, cs: &CriticalSection) -> bool {
+        let pending = CHUNKS[C::INDEX].borrow(cs).get().remaining > 0;
+        if !pending || !self.transfer_complete() || self.transfer_error() || !self.is_enabled() {
+            return false;
+        }
+
+        self.disable();
+        self.next_chunk(cs);
+        // NOTE(unsafe) the next chunk lies within the transfer programmed by
+        // `set_transfer`, whose owner keeps the buffers alive until it is done
+        unsafe { self.enable() };
+        true
+    }
+
+    fn next_chunk(&mut self, cs: &CriticalSection) {
+        let cell = CHUNKS[C::INDEX].borrow(cs);
+        let mut chunks = cell.get();
+
+        let len = chunks.remaining.min(MAX_CHUNK);
+        // NOTE(unsafe) the chunk lies within the transfer passed to
+        // `set_transfer`, and the channel is disabled
+        unsafe {
+            self.set_paddr(chunks.paddr);
+            self.set_maddr(chunks.maddr);
+            self.set_ndt(len as u16);
+        }
+
+        chunks.remaining -= len;
+        // the addresses past the last chunk may overflow, but are never used
+        chunks.paddr = chunks.paddr.wrapping_add(chunks.pstep * len as u32);
+        chunks.maddr = chunks.maddr.wrapping_add(chunks.mstep * len as u32);
+        cell.set(chunks);
+    }
+}
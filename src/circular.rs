@@ -0,0 +1,277 @@
+//! Continuous peripheral-to-memory transfers in circular mode.
+//!
+//! `CircularTransfer` is the plain circular transfer. `PingPong` builds on
+//! it to double-buffer the incoming data, using the half-transfer and
+//! transfer-complete flags to tell which half of the buffer is safe to read.
+
+use crate::{
+    region, Channel, ChannelId, Direction, DmaRequest, DmaWriteBuffer, PeripheralSource,
+    TransferConfig, Word,
+};
+use core::{
+    ptr,
+    sync::atomic::{self, Ordering},
+};
+
+/// Safe abstraction of a circular DMA transfer from a peripheral.
+///
+/// The DMA keeps filling the buffer, wrapping around to its start whenever
+/// the end is reached. The transfer never completes on its own, it runs
+/// until `stop` is called or it is dropped.
+///
+/// Unlike `Transfer`, there is no `nonstatic` constructor, since a circular
+/// transfer is meant to outlive the function that starts it.
+pub struct CircularTransfer<P, B, C: ChannelId> {
+    // always `Some` outside of `Drop::drop`
+    inner: Option<CircularInner<P, B, C>>,
+    len: usize,
+}
+
+impl<P, B, C: ChannelId> CircularTransfer<P, B, C> {
+    /// Start a circular transfer from `src` into `dst` on the given channel.
+    pub fn start(dma: Channel<C>, src: P, dst: B) -> Self
+    where
+        P: PeripheralSource + DmaRequest<Channel = C>,
+        B: DmaWriteBuffer<Word = P::Word> + 'static,
+    {
+        let config = TransferConfig {
+            direction: Direction::PeripheralToMemory,
+            peripheral_increment: false,
+            circular: true,
+            ..TransferConfig::default()
+        };
+        Self::start_with(dma, config, src, dst)
+    }
+
+    /// Start a circular transfer from `src` into `dst` on the given channel,
+    /// using the given configuration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `config` doesn't describe a circular peripheral-to-memory
+    /// transfer with a fixed peripheral address, if it enables the half
+    /// transfer or transfer complete interrupt, if it sets data sizes that
+    /// don't match the word types, if `dst` is empty or longer than 65535
+    /// words, if the DMA can't access `dst` or the data register of `src`,
+    /// or if one of them is misaligned. Unlike one-shot transfers, circular
+    /// transfers can't be split into chunks.
+    pub fn start_with(mut dma: Channel<C>, config: TransferConfig, src: P, mut dst: B) -> Self
+    where
+        P: PeripheralSource + DmaRequest<Channel = C>,
+        B: DmaWriteBuffer<Word = P::Word> + 'static,
+    {
+        assert_eq!(config.direction, Direction::PeripheralToMemory);
+        assert!(config.circular);
+        assert!(!config.peripheral_increment);
+        // The interrupt handler masks these interrupts after their first
+        // occurrence, and the flags they signal are observed by polling.
+        assert!(!config.half_transfer_interrupt);
+        assert!(!config.transfer_complete_interrupt);
+        if let Some(size) = config.peripheral_size {
+            assert_eq!(size, P::Word::SIZE);
+        }
+        if let Some(size) = config.memory_size {
+            assert_eq!(size, P::Word::SIZE);
+        }
+
+        dma.configure(&config);
+        let (dst_ptr, len) = dst.dma_write_buffer();
+        assert!(len > 0 && len <= usize::from(u16::MAX));
+        assert!(region::dma_accessible(dst_ptr, len));
+        assert!(region::dma_accessible(src.address() as *const P::Word, 1));
+        assert!(region::dma_aligned(dst_ptr as *const P::Word));
+        assert!(region::dma_aligned(src.address() as *const P::Word));
+
+        // NOTE(unsafe) the addresses and length were checked above, and the
+        // transfer owns `src` and `dst`, which are `'static`, until it is
+        // stopped
+        unsafe {
+            dma.set_paddr(src.address());
+            dma.set_maddr(dst_ptr as *mut u8 as u32);
+            dma.set_psize(P::Word::SIZE);
+            dma.set_msize(P::Word::SIZE);
+            dma.set_ndt(len as u16);
+        }
+
+        // Prevent preceding reads/writes on the buffer from being moved past
+        // the DMA enable modify (i.e. after the transfer has started).
+        atomic::compiler_fence(Ordering::Release);
+
+        // NOTE(unsafe) see above
+        unsafe { dma.enable() };
+
+        CircularTransfer {
+            inner: Some(CircularInner { dma, src, dst }),
+            len,
+        }
+    }
+
+    /// Index of the word the DMA writes next.
+    ///
+    /// All words before this index have been written during the current
+    /// pass over the buffer.
+    pub fn position(&self) -> usize {
+        let inner = self.inner.as_ref().unwrap();
+        let remaining = usize::from(inner.dma.ndt());
+        (self.len - remaining) % self.len
+    }
+
+    fn dma(&mut self) -> &mut Channel<C> {
+        &mut self.inner.as_mut().unwrap().dma
+    }
+
+    /// Stop the transfer and hand back the channel, peripheral and buffer.
+    pub fn stop(mut self) -> (Channel<C>, P, B) {
+        let mut inner = self.inner.take().unwrap();
+        inner.stop();
+        (inner.dma, inner.src, inner.dst)
+    }
+}
+
+/// Error returned by `PingPong::read`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PingPongError {
+    /// The DMA overwrote data before or while the application read it.
+    Overrun,
+    /// The DMA reported a transfer error, caused by a bus error on one of
+    /// the accessed addresses. The DMA disabled the channel, so no more
+    /// data arrives.
+    BusError,
+}
+
+/// Double-buffered circular transfer from a peripheral.
+///
+/// The buffer is split into two halves. While the DMA fills one half, the
+/// application can copy the other one out via `read`.
+pub struct PingPong<P: PeripheralSource, B, C: ChannelId> {
+    transfer: CircularTransfer<P, B, C>,
+    ptr: *const P::Word,
+}
+
+impl<P, B, C> PingPong<P, B, C>
+where
+    P: PeripheralSource + DmaRequest<Channel = C>,
+    B: DmaWriteBuffer<Word = P::Word> + 'static,
+    C: ChannelId,
+{
+    /// Start a double-buffered transfer from `src` into `dst` on the given
+    /// channel.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dst` is empty or has an odd length.
+    pub fn start(dma: Channel<C>, src: P, mut dst: B) -> Self {
+        let (ptr, len) = dst.dma_write_buffer();
+        assert!(len % 2 == 0);
+
+        let transfer = CircularTransfer::start(dma, src, dst);
+        PingPong {
+            transfer,
+            ptr: ptr as *const P::Word,
+        }
+    }
+
+    /// Wait for the DMA to fill a half of the buffer and copy it into `buf`.
+    ///
+    /// Returns the number of words copied, which is half the length of the
+    /// DMA buffer. The DMA may still write to the buffer, so it is never
+    /// handed out by reference. Instead, the half is copied with volatile
+    /// reads.
+    ///
+    /// Returns `PingPongError::Overrun` if the DMA filled both halves since
+    /// the last call, or if it came back to the copied half before the copy
+    /// was finished. In both cases the data in `buf` may be partly
+    /// overwritten. Returns `PingPongError::BusError` if the transfer failed,
+    /// instead of waiting for data that never arrives.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buf` is shorter than half the DMA buffer.
+    pub fn read(&mut self, buf: &mut [P::Word]) -> Result<usize, PingPongError> {
+        let half = self.transfer.len / 2;
+        assert!(buf.len() >= half);
+        let dma = self.transfer.dma();
+
+        let (first_half, second_half) = loop {
+            if dma.transfer_error() {
+                return Err(PingPongError::BusError);
+            }
+            let first_half = dma.half_transfer();
+            let second_half = dma.transfer_complete();
+            if first_half || second_half {
+                break (first_half, second_half);
+            }
+        };
+
+        if first_half && second_half {
+            dma.clear_half_transfer();
+            dma.clear_transfer_complete();
+            return Err(PingPongError::Overrun);
+        }
+
+        let offset = if first_half {
+            dma.clear_half_transfer();
+            0
+        } else {
+            dma.clear_transfer_complete();
+            half
+        };
+
+        // Prevent reads of the buffer from being moved ahead of the flag
+        // checks (i.e. before the DMA is done with this half).
+        atomic::compiler_fence(Ordering::Acquire);
+
+        for (i, word) in buf[..half].iter_mut().enumerate() {
+            // NOTE(unsafe) the index is within the DMA buffer. The DMA may
+            // write to it concurrently, which the volatile read tolerates.
+            *word = unsafe { ptr::read_volatile(self.ptr.add(offset + i)) };
+        }
+
+        // Prevent reads of the buffer from being moved past the flag check
+        // below.
+        atomic::compiler_fence(Ordering::SeqCst);
+
+        let dma = self.transfer.dma();
+        let wrapped = if first_half {
+            dma.transfer_complete()
+        } else {
+            dma.half_transfer()
+        };
+
+        if wrapped {
+            Err(PingPongError::Overrun)
+        } else {
+            Ok(half)
+        }
+    }
+
+    /// Stop the transfer and hand back the channel, peripheral and buffer.
+    pub fn stop(self) -> (Channel<C>, P, B) {
+        self.transfer.stop()
+    }
+}
+
+struct CircularInner<P, B, C: ChannelId> {
+    dma: Channel<C>,
+    src: P,
+    dst: B,
+}
+
+impl<P, B, C: ChannelId> CircularInner<P, B, C> {
+    fn stop(&mut self) {
+        self.dma.disable();
+
+        // Prevent subsequent reads/writes on the buffer from being moved
+        // ahead of the DMA disable modify (i.e. before the transfer is
+        // stopped).
+        atomic::compiler_fence(Ordering::Acquire);
+    }
+}
+
+impl<P, B, C: ChannelId> Drop for CircularTransfer<P, B, C> {
+    fn drop(&mut self) {
+        if let Some(mut inner) = self.inner.take() {
+            inner.stop();
+        }
+    }
+}
@@ -0,0 +1,163 @@
+//! Validated DMA channel configuration.
+//!
+//! A `TransferConfig` can only be obtained through `TransferConfig::default`
+//! or `TransferConfigBuilder::build`, which rejects combinations of settings
+//! the DMA controller doesn't support.
+
+use crate::WordSize;
+
+/// Direction of a DMA transfer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    PeripheralToMemory,
+    MemoryToPeripheral,
+    MemoryToMemory,
+}
+
+/// Priority of a DMA channel, relative to the other channels of the same
+/// controller.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Priority {
+    Low,
+    Medium,
+    High,
+    VeryHigh,
+}
+
+/// Error returned by `TransferConfigBuilder::build`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigError {
+    /// Circular mode was requested for a memory-to-memory transfer.
+    CircularMemToMem,
+}
+
+/// Configuration of a DMA channel for a transfer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransferConfig {
+    pub(crate) direction: Direction,
+    pub(crate) priority: Priority,
+    pub(crate) peripheral_increment: bool,
+    pub(crate) memory_increment: bool,
+    pub(crate) peripheral_size: Option<WordSize>,
+    pub(crate) memory_size: Option<WordSize>,
+    pub(crate) circular: bool,
+    pub(crate) transfer_complete_interrupt: bool,
+    pub(crate) half_transfer_interrupt: bool,
+    pub(crate) transfer_error_interrupt: bool,
+}
+
+impl TransferConfig {
+    pub fn builder() -> TransferConfigBuilder {
+        TransferConfigBuilder {
+            config: Self::default(),
+        }
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+
+    pub fn circular(&self) -> bool {
+        self.circular
+    }
+}
+
+/// The default configuration is a one-shot memory-to-memory transfer with
+/// low priority, incrementing both addresses and no interrupts enabled.
+impl Default for TransferConfig {
+    fn default() -> Self {
+        Self {
+            direction: Direction::MemoryToMemory,
+            priority: Priority::Low,
+            peripheral_increment: true,
+            memory_increment: true,
+            peripheral_size: None,
+            memory_size: None,
+            circular: false,
+            transfer_complete_interrupt: false,
+            half_transfer_interrupt: false,
+            transfer_error_interrupt: false,
+        }
+    }
+}
+
+/// Builder for `TransferConfig`, starting from the default configuration.
+#[derive(Clone, Copy, Debug)]
+pub struct TransferConfigBuilder {
+    config: TransferConfig,
+}
+
+impl TransferConfigBuilder {
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.config.direction = direction;
+        self
+    }
+
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.config.priority = priority;
+        self
+    }
+
+    pub fn peripheral_increment(mut self, enable: bool) -> Self {
+        self.config.peripheral_increment = enable;
+        self
+    }
+
+    pub fn memory_increment(mut self, enable: bool) -> Self {
+        self.config.memory_increment = enable;
+        self
+    }
+
+    /// Set the peripheral data size.
+    ///
+    /// If not set, the data size is derived from the word type of the
+    /// peripheral side of the transfer. If set, it must match that word type.
+    pub fn peripheral_size(mut self, size: WordSize) -> Self {
+        self.config.peripheral_size = Some(size);
+        self
+    }
+
+    /// Set the memory data size.
+    ///
+    /// If not set, the data size is derived from the word type of the
+    /// memory side of the transfer. If set, it must match that word type.
+    pub fn memory_size(mut self, size: WordSize) -> Self {
+        self.config.memory_size = Some(size);
+        self
+    }
+
+    pub fn circular(mut self, enable: bool) -> Self {
+        self.config.circular = enable;
+        self
+    }
+
+    pub fn transfer_complete_interrupt(mut self, enable: bool) -> Self {
+        self.config.transfer_complete_interrupt = enable;
+        self
+    }
+
+    pub fn half_transfer_interrupt(mut self, enable: bool) -> Self {
+        self.config.half_transfer_interrupt = enable;
+        self
+    }
+
+    pub fn transfer_error_interrupt(mut self, enable: bool) -> Self {
+        self.config.transfer_error_interrupt = enable;
+        self
+    }
+
+    /// Validate the configuration.
+    pub fn build(self) -> Result<TransferConfig, ConfigError> {
+        let config = self.config;
+
+        if config.circular && config.direction == Direction::MemoryToMemory {
+            return Err(ConfigError::CircularMemToMem);
+        }
+
+        Ok(config)
+    }
+}
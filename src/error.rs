@@ -0,0 +1,57 @@
+//! Errors of one-shot transfers.
+//!
+//! A failed transfer still owns the channel and both buffers. Instead of
+//! dropping them, `TransferError` hands them back to the caller, so they
+//! can be reused, e.g. to retry the transfer.
+
+use crate::{dma1, Channel, ChannelId};
+use core::fmt;
+
+/// Result of waiting for a transfer.
+pub type TransferResult<R, W, C = dma1::C1> = Result<(Channel<C>, R, W), TransferError<R, W, C>>;
+
+/// Reason for a transfer to fail.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorReason {
+    /// The DMA reported a transfer error, caused by a bus error on one of
+    /// the accessed addresses.
+    BusError,
+    /// The transfer didn't finish in time.
+    Timeout,
+    /// The transfer was aborted before it finished.
+    Aborted,
+    /// The destination is too short to hold the source.
+    LengthMismatch,
+    /// The source is empty, so there is nothing to transfer.
+    ZeroLength,
+    /// One of the buffers lies in memory the DMA can't access.
+    InaccessibleMemory,
+    /// One of the buffers is not aligned to the size of its words.
+    Misaligned,
+}
+
+/// Error of a failed transfer, handing back the channel and buffers.
+///
+/// The channel is disabled and the DMA no longer accesses the buffers.
+pub struct TransferError<R, W, C: ChannelId = dma1::C1> {
+    pub dma: Channel<C>,
+    pub src: R,
+    pub dst: W,
+    pub reason: ErrorReason,
+    /// Number of words the DMA had left to transfer.
+    pub remaining: usize,
+    /// Number of words the DMA transferred before it was stopped.
+    ///
+    /// Only that many words of the destination have been written.
+    pub transferred: usize,
+}
+
+impl<R, W, C: ChannelId> fmt::Debug for TransferError<R, W, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TransferError")
+            .field("reason", &self.reason)
+            .field("remaining", &self.remaining)
+            .field("transferred", &self.transferred)
+            .finish()
+    }
+}
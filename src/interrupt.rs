@@ -0,0 +1,75 @@
+//! Crate-provided DMA interrupt handlers.
+//!
+//! The handlers mask the interrupts that fired, but leave the flags set for
+//! the owner of the channel (usually a `Transfer`) to observe. That way,
+//! waiting code can sleep until the DMA interrupt wakes it up, and then
+//! check the flags as usual.
+//!
+//! The interrupts are unmasked in the NVIC when a channel configured with
+//! interrupts enabled is started.
+//!
+//! The handlers are only defined with the `interrupt-handlers` feature.
+//! Applications that need the DMA interrupts for other purposes disable it
+//! and call `Channel::on_interrupt` from their own handlers.
+
+use crate::{dma1, dma2, pac::interrupt, Channel};
+
+#[interrupt]
+fn DMA1_CH1() {
+    Channel::<dma1::C1>::on_interrupt();
+}
+
+#[interrupt]
+fn DMA1_CH2() {
+    Channel::<dma1::C2>::on_interrupt();
+}
+
+#[interrupt]
+fn DMA1_CH3() {
+    Channel::<dma1::C3>::on_interrupt();
+}
+
+#[interrupt]
+fn DMA1_CH4() {
+    Channel::<dma1::C4>::on_interrupt();
+}
+
+#[interrupt]
+fn DMA1_CH5() {
+    Channel::<dma1::C5>::on_interrupt();
+}
+
+#[interrupt]
+fn DMA1_CH6() {
+    Channel::<dma1::C6>::on_interrupt();
+}
+
+#[interrupt]
+fn DMA1_CH7() {
+    Channel::<dma1::C7>::on_interrupt();
+}
+
+#[interrupt]
+fn DMA2_CH1() {
+    Channel::<dma2::C1>::on_interrupt();
+}
+
+#[interrupt]
+fn DMA2_CH2() {
+    Channel::<dma2::C2>::on_interrupt();
+}
+
+#[interrupt]
+fn DMA2_CH3() {
+    Channel::<dma2::C3>::on_interrupt();
+}
+
+#[interrupt]
+fn DMA2_CH4() {
+    Channel::<dma2::C4>::on_interrupt();
+}
+
+#[interrupt]
+fn DMA2_CH5() {
+    Channel::<dma2::C5>::on_interrupt();
+}
@@ -1,138 +1,776 @@
-#![no_std]
-
+#![cfg_attr(not(test), no_std)]
+
+mod buffer;
+mod channel;
+mod chunk;
+mod circular;
+mod config;
+mod error;
+#[cfg(all(target_os = "none", feature = "interrupt-handlers"))]
+mod interrupt;
+mod peripheral;
+mod region;
+mod timeout;
 mod traits;
+#[cfg(feature = "async")]
+mod waker;
+
+#[cfg(test)]
+mod tests;
 
+#[cfg(target_os = "none")]
 use panic_semihosting as _;
 
 use core::sync::atomic::{self, Ordering};
+use core::task::Poll;
+#[cfg(feature = "async")]
+use core::{future::Future, pin::Pin, task::Context};
+use cortex_m::asm;
+#[cfg(target_os = "none")]
+use cortex_m::interrupt::free as critical_section;
 use stm32f3::stm32f303 as pac;
 
-pub use traits::{DmaReadBuffer, DmaWriteBuffer};
+pub use channel::{dma1, dma2, Channel, ChannelId, DmaClock, DmaExt};
+pub use circular::{CircularTransfer, PingPong, PingPongError};
+pub use config::{ConfigError, Direction, Priority, TransferConfig, TransferConfigBuilder};
+pub use error::{ErrorReason, TransferError, TransferResult};
+pub use peripheral::{
+    join_duplex, split_duplex, DmaRequest, PeripheralDestination, PeripheralSource, Rx, Tx,
+};
+#[cfg(feature = "async")]
+pub use timeout::WithTimeout;
+pub use timeout::{SysTickClock, TimeSource};
+pub use traits::{
+    Aligned, Alignment, DmaReadBuffer, DmaWord, DmaWriteBuffer, Word, WordSize, A1, A32, A4,
+};
+
+#[cfg(feature = "derive")]
+pub use dma_poc_derive::DmaWord;
+
+/// Run `f` in a critical section.
+///
+/// Host builds have no interrupts to disable, so `f` just runs. Host tests
+/// use a separate mock channel each, and thus never share any state guarded
+/// by critical sections.
+#[cfg(not(target_os = "none"))]
+fn critical_section<F, T>(f: F) -> T
+where
+    F: FnOnce(&cortex_m::interrupt::CriticalSection) -> T,
+{
+    // NOTE(unsafe) see above
+    f(unsafe { &cortex_m::interrupt::CriticalSection::new() })
+}
 
-/// Thin wrapper around the DMA1 peripheral, using channel 1.
-pub struct Dma(pac::DMA1);
+/// DMA1 channel 1, the channel used by the convenience constructors.
+pub type Dma = Channel<dma1::C1>;
 
 impl Dma {
+    /// Set up channel 1 of DMA1 for memory-to-memory transfers.
+    ///
+    /// This takes the device peripherals and therefore panics if they were
+    /// already taken. Applications that own their peripherals should use
+    /// `Dma::new` instead. Like `Dma::new`, this leaves the other channels
+    /// of DMA1 unusable.
     pub fn mem2mem() -> Self {
-        let device = pac::Peripherals::take().unwrap();
-
-        // enable DMA1 peripheral
-        device.RCC.ahbenr.modify(|_, w| w.dma1en().enabled());
-
-        // setup channel 1 for mem2mem transfer
-        let dma1 = device.DMA1;
-        dma1.ch1.cr.write(|w| {
-            w.dir().from_peripheral();
-            w.pinc().enabled();
-            w.minc().enabled();
-            w.psize().bits8();
-            w.msize().bits8();
-            w.mem2mem().enabled()
-        });
+        let mut device = pac::Peripherals::take().unwrap();
+        Self::new(device.DMA1, &mut device.RCC)
+    }
 
-        Self(dma1)
+    /// Set up channel 1 of DMA1 for memory-to-memory transfers.
+    ///
+    /// **This consumes the whole DMA1 controller, so its other six channels
+    /// can't be used anymore.** Applications that need more than one
+    /// channel should split the controller with `DmaExt::split` instead, and
+    /// start transfers on its channels with `Transfer::start_on`.
+    pub fn new<K: DmaClock>(dma1: pac::DMA1, clock: &mut K) -> Self {
+        let mut dma = dma1.split(clock).ch1;
+        dma.configure(&TransferConfig::default());
+        dma
     }
+}
 
-    pub fn set_paddr(&mut self, addr: u32) {
-        self.0.ch1.par.write(|w| w.pa().bits(addr));
+impl Channel<dma2::C1> {
+    /// Set up channel 1 of DMA2 for memory-to-memory transfers.
+    ///
+    /// **This consumes the whole DMA2 controller, so its other four channels
+    /// can't be used anymore.** Applications that need more than one
+    /// channel should split the controller with `DmaExt::split` instead, and
+    /// start transfers on its channels with `Transfer::start_on`.
+    pub fn new<K: DmaClock>(dma2: pac::DMA2, clock: &mut K) -> Self {
+        let mut dma = dma2.split(clock).ch1;
+        dma.configure(&TransferConfig::default());
+        dma
     }
+}
+
+/// Safe abstraction of a DMA read transfer.
+pub struct Transfer<R, W, C: ChannelId = dma1::C1> {
+    // always `Some` outside of `Drop::drop`
+    inner: Option<TransferInner<R, W, C>>,
+}
 
-    pub fn set_maddr(&mut self, addr: u32) {
-        self.0.ch1.mar.write(|w| w.ma().bits(addr));
+impl<R, W> Transfer<R, W> {
+    pub fn start(src: R, dst: W) -> Self
+    where
+        R: DmaReadBuffer + 'static,
+        W: DmaWriteBuffer<Word = R::Word> + 'static,
+        R::Word: Word,
+    {
+        unsafe { Self::start_nonstatic(src, dst) }
     }
 
-    pub fn set_ndt(&mut self, len: u16) {
-        self.0.ch1.ndtr.write(|w| w.ndt().bits(len));
+    /// # Safety
+    ///
+    /// If `dst` is not `'static`, callers must ensure that `mem::forget`
+    /// is never called on the returned `Transfer`.
+    pub unsafe fn start_nonstatic(src: R, dst: W) -> Self
+    where
+        R: DmaReadBuffer,
+        W: DmaWriteBuffer<Word = R::Word>,
+        R::Word: Word,
+    {
+        Self::start_on_nonstatic(Dma::mem2mem(), src, dst)
     }
 
-    pub fn enable(&mut self) {
-        // clear interrupt flags
-        self.0.ifcr.write(|w| w.cgif1().set_bit());
+    /// Start a transfer between buffers of different word sizes.
+    ///
+    /// See `start_packing_on` for how data items are converted.
+    pub fn start_packing(src: R, dst: W) -> Self
+    where
+        R: DmaReadBuffer + 'static,
+        W: DmaWriteBuffer + 'static,
+        R::Word: Word,
+        W::Word: Word,
+    {
+        unsafe { Self::start_packing_nonstatic(src, dst) }
+    }
 
-        self.0.ch1.cr.modify(|_, w| w.en().enabled());
+    /// Start a transfer between buffers of different word sizes.
+    ///
+    /// See `start_packing_on` for how data items are converted.
+    ///
+    /// # Safety
+    ///
+    /// If `dst` is not `'static`, callers must ensure that `mem::forget`
+    /// is never called on the returned `Transfer`.
+    pub unsafe fn start_packing_nonstatic(src: R, dst: W) -> Self
+    where
+        R: DmaReadBuffer,
+        W: DmaWriteBuffer,
+        R::Word: Word,
+        W::Word: Word,
+    {
+        Self::start_packing_on_nonstatic(Dma::mem2mem(), src, dst)
     }
+}
 
-    pub fn disable(&mut self) {
-        self.0.ch1.cr.modify(|_, w| w.en().disabled());
+impl<R, W, C: ChannelId> Transfer<R, W, C> {
+    /// Start a transfer on the given channel.
+    ///
+    /// The channel is reconfigured for a memory-to-memory transfer and
+    /// handed back by `wait`, so it can be reused for further transfers.
+    pub fn start_on(dma: Channel<C>, src: R, dst: W) -> Self
+    where
+        R: DmaReadBuffer + 'static,
+        W: DmaWriteBuffer<Word = R::Word> + 'static,
+        R::Word: Word,
+    {
+        Self::start_with(dma, TransferConfig::default(), src, dst)
     }
 
-    pub fn transfer_complete(&self) -> bool {
-        self.0.isr.read().tcif1().bit_is_set()
+    /// Start a transfer on the given channel.
+    ///
+    /// See `start_on` for how the channel is used.
+    ///
+    /// # Safety
+    ///
+    /// If `dst` is not `'static`, callers must ensure that `mem::forget`
+    /// is never called on the returned `Transfer`.
+    pub unsafe fn start_on_nonstatic(dma: Channel<C>, src: R, dst: W) -> Self
+    where
+        R: DmaReadBuffer,
+        W: DmaWriteBuffer<Word = R::Word>,
+        R::Word: Word,
+    {
+        Self::start_with_nonstatic(dma, TransferConfig::default(), src, dst)
     }
 
-    pub fn transfer_error(&self) -> bool {
-        self.0.isr.read().teif1().bit_is_set()
+    /// Start a transfer on the given channel, checking the buffers first.
+    ///
+    /// Unlike `start_on`, this doesn't panic if `src` is empty or longer
+    /// than `dst`, if one of the buffers lies in memory the DMA can't access
+    /// (like the CCM RAM), or if one of them is not aligned to the size of
+    /// its words. Instead, it hands back the channel and buffers in a
+    /// `TransferError`. Buffers longer than 65535 words are fine, since long
+    /// transfers are split into chunks.
+    ///
+    /// See `start_on` for how the channel is used.
+    pub fn try_start_on(dma: Channel<C>, src: R, dst: W) -> Result<Self, TransferError<R, W, C>>
+    where
+        R: DmaReadBuffer + 'static,
+        W: DmaWriteBuffer<Word = R::Word> + 'static,
+        R::Word: Word,
+    {
+        unsafe { Self::try_start_on_nonstatic(dma, src, dst) }
     }
-}
 
-/// Safe abstraction of a DMA read transfer.
-pub struct Transfer<R, W> {
-    // always `Some` outside of `Drop::drop`
-    inner: Option<TransferInner<R, W>>,
-}
+    /// Start a transfer on the given channel, checking the buffers first.
+    ///
+    /// See `try_start_on` for the checks performed and how the channel is
+    /// used.
+    ///
+    /// # Safety
+    ///
+    /// If `dst` is not `'static`, callers must ensure that `mem::forget`
+    /// is never called on the returned `Transfer`.
+    pub unsafe fn try_start_on_nonstatic(
+        dma: Channel<C>,
+        src: R,
+        dst: W,
+    ) -> Result<Self, TransferError<R, W, C>>
+    where
+        R: DmaReadBuffer,
+        W: DmaWriteBuffer<Word = R::Word>,
+        R::Word: Word,
+    {
+        Self::try_start_inner(dma, TransferConfig::default(), src, dst)
+    }
 
-impl<R, W> Transfer<R, W> {
-    pub fn start(src: R, dst: W) -> Self
+    /// Start a transfer on the given channel, using the given configuration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `config` doesn't describe a memory-to-memory transfer, if
+    /// it sets data sizes that don't match the buffer word types, if `src`
+    /// is empty or longer than `dst`, if the DMA can't access one of the
+    /// buffers, or if one of them is misaligned.
+    pub fn start_with(dma: Channel<C>, config: TransferConfig, src: R, dst: W) -> Self
+    where
+        R: DmaReadBuffer + 'static,
+        W: DmaWriteBuffer<Word = R::Word> + 'static,
+        R::Word: Word,
+    {
+        unsafe { Self::start_with_nonstatic(dma, config, src, dst) }
+    }
+
+    /// Start a transfer on the given channel, using the given configuration.
+    ///
+    /// See `start_with` for the requirements on `config`.
+    ///
+    /// # Safety
+    ///
+    /// If `dst` is not `'static`, callers must ensure that `mem::forget`
+    /// is never called on the returned `Transfer`.
+    pub unsafe fn start_with_nonstatic(
+        dma: Channel<C>,
+        config: TransferConfig,
+        src: R,
+        dst: W,
+    ) -> Self
+    where
+        R: DmaReadBuffer,
+        W: DmaWriteBuffer<Word = R::Word>,
+        R::Word: Word,
+    {
+        Self::start_inner(dma, config, src, dst)
+    }
+
+    /// Start a transfer between buffers of different word sizes on the given
+    /// channel.
+    ///
+    /// The DMA reads each data item with the size of `R::Word` and writes
+    /// it with the size of `W::Word`. Narrower items are zero-extended,
+    /// wider items are truncated to their least significant bits. Buffer
+    /// lengths are compared in data items, not in bytes.
+    pub fn start_packing_on(dma: Channel<C>, src: R, dst: W) -> Self
     where
         R: DmaReadBuffer + 'static,
         W: DmaWriteBuffer + 'static,
+        R::Word: Word,
+        W::Word: Word,
     {
-        unsafe { Self::start_nonstatic(src, dst) }
+        unsafe { Self::start_packing_on_nonstatic(dma, src, dst) }
     }
 
+    /// Start a transfer between buffers of different word sizes on the given
+    /// channel.
+    ///
+    /// See `start_packing_on` for how data items are converted.
+    ///
     /// # Safety
     ///
     /// If `dst` is not `'static`, callers must ensure that `mem::forget`
     /// is never called on the returned `Transfer`.
-    pub unsafe fn start_nonstatic(src: R, mut dst: W) -> Self
+    pub unsafe fn start_packing_on_nonstatic(dma: Channel<C>, src: R, dst: W) -> Self
     where
         R: DmaReadBuffer,
         W: DmaWriteBuffer,
+        R::Word: Word,
+        W::Word: Word,
+    {
+        Self::start_inner(dma, TransferConfig::default(), src, dst)
+    }
+
+    /// Start a peripheral-to-memory transfer on the given channel.
+    ///
+    /// The DMA reads from the data register of `src` and fills the whole
+    /// `dst` buffer. The peripheral address is not incremented. `dma` must
+    /// be the channel the DMA request of `src` is wired to.
+    pub fn from_peripheral(dma: Channel<C>, src: R, dst: W) -> Self
+    where
+        R: PeripheralSource + DmaRequest<Channel = C>,
+        W: DmaWriteBuffer<Word = R::Word> + 'static,
+    {
+        unsafe { Self::from_peripheral_nonstatic(dma, src, dst) }
+    }
+
+    /// Start a peripheral-to-memory transfer on the given channel.
+    ///
+    /// See `from_peripheral` for how the transfer is performed.
+    ///
+    /// # Safety
+    ///
+    /// If `dst` is not `'static`, callers must ensure that `mem::forget`
+    /// is never called on the returned `Transfer`.
+    pub unsafe fn from_peripheral_nonstatic(dma: Channel<C>, src: R, dst: W) -> Self
+    where
+        R: PeripheralSource + DmaRequest<Channel = C>,
+        W: DmaWriteBuffer<Word = R::Word>,
+    {
+        let config = TransferConfig {
+            direction: Direction::PeripheralToMemory,
+            peripheral_increment: false,
+            ..TransferConfig::default()
+        };
+        Self::from_peripheral_with_nonstatic(dma, config, src, dst)
+    }
+
+    /// Start a peripheral-to-memory transfer on the given channel, using the
+    /// given configuration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `config` doesn't describe a one-shot peripheral-to-memory
+    /// transfer with a fixed peripheral address, if it sets data sizes that
+    /// don't match the word types, if `dst` is empty, if the DMA can't
+    /// access `dst` or the data register of `src`, or if one of them is
+    /// misaligned. Use `CircularTransfer` for circular transfers.
+    pub fn from_peripheral_with(dma: Channel<C>, config: TransferConfig, src: R, dst: W) -> Self
+    where
+        R: PeripheralSource + DmaRequest<Channel = C>,
+        W: DmaWriteBuffer<Word = R::Word> + 'static,
     {
-        let mut dma = Dma::mem2mem();
+        unsafe { Self::from_peripheral_with_nonstatic(dma, config, src, dst) }
+    }
+
+    /// Start a peripheral-to-memory transfer on the given channel, using the
+    /// given configuration.
+    ///
+    /// See `from_peripheral_with` for the requirements on `config`.
+    ///
+    /// # Safety
+    ///
+    /// If `dst` is not `'static`, callers must ensure that `mem::forget`
+    /// is never called on the returned `Transfer`.
+    pub unsafe fn from_peripheral_with_nonstatic(
+        mut dma: Channel<C>,
+        config: TransferConfig,
+        src: R,
+        mut dst: W,
+    ) -> Self
+    where
+        R: PeripheralSource + DmaRequest<Channel = C>,
+        W: DmaWriteBuffer<Word = R::Word>,
+    {
+        assert_eq!(config.direction, Direction::PeripheralToMemory);
+        assert!(!config.circular);
+        assert!(!config.peripheral_increment);
+        if let Some(size) = config.peripheral_size {
+            assert_eq!(size, R::Word::SIZE);
+        }
+        if let Some(size) = config.memory_size {
+            assert_eq!(size, R::Word::SIZE);
+        }
+
+        dma.configure(&config);
         {
-            let (src_ptr, src_len) = src.dma_read_buffer();
             let (dst_ptr, dst_len) = dst.dma_write_buffer();
-            assert!(dst_len >= src_len);
+            assert!(dst_len > 0);
+            assert!(region::dma_accessible(dst_ptr, dst_len));
+            assert!(region::dma_accessible(src.address() as *const R::Word, 1));
+            assert!(region::dma_aligned(dst_ptr as *const R::Word));
+            assert!(region::dma_aligned(src.address() as *const R::Word));
+
+            dma.set_psize(R::Word::SIZE);
+            dma.set_msize(R::Word::SIZE);
+            dma.set_transfer(src.address(), dst_ptr as *mut u8 as u32, dst_len);
+        }
+
+        Self::launch(dma, src, dst)
+    }
+
+    /// Start a memory-to-peripheral transfer on the given channel.
+    ///
+    /// The DMA reads the whole `src` buffer and writes it to the data
+    /// register of `dst`. The peripheral address is not incremented. `dma`
+    /// must be the channel the DMA request of `dst` is wired to.
+    pub fn to_peripheral(dma: Channel<C>, src: R, dst: W) -> Self
+    where
+        R: DmaReadBuffer<Word = W::Word> + 'static,
+        W: PeripheralDestination + DmaRequest<Channel = C>,
+    {
+        unsafe { Self::to_peripheral_nonstatic(dma, src, dst) }
+    }
+
+    /// Start a memory-to-peripheral transfer on the given channel.
+    ///
+    /// See `to_peripheral` for how the transfer is performed.
+    ///
+    /// # Safety
+    ///
+    /// If `src` is not `'static`, callers must ensure that `mem::forget`
+    /// is never called on the returned `Transfer`.
+    pub unsafe fn to_peripheral_nonstatic(dma: Channel<C>, src: R, dst: W) -> Self
+    where
+        R: DmaReadBuffer<Word = W::Word>,
+        W: PeripheralDestination + DmaRequest<Channel = C>,
+    {
+        let config = TransferConfig {
+            direction: Direction::MemoryToPeripheral,
+            peripheral_increment: false,
+            ..TransferConfig::default()
+        };
+        Self::to_peripheral_with_nonstatic(dma, config, src, dst)
+    }
+
+    /// Start a memory-to-peripheral transfer on the given channel, using the
+    /// given configuration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `config` doesn't describe a one-shot memory-to-peripheral
+    /// transfer with a fixed peripheral address, if it sets data sizes that
+    /// don't match the word types, if `src` is empty, if the DMA can't
+    /// access `src` or the data register of `dst`, or if one of them is
+    /// misaligned.
+    pub fn to_peripheral_with(dma: Channel<C>, config: TransferConfig, src: R, dst: W) -> Self
+    where
+        R: DmaReadBuffer<Word = W::Word> + 'static,
+        W: PeripheralDestination + DmaRequest<Channel = C>,
+    {
+        unsafe { Self::to_peripheral_with_nonstatic(dma, config, src, dst) }
+    }
+
+    /// Start a memory-to-peripheral transfer on the given channel, using the
+    /// given configuration.
+    ///
+    /// See `to_peripheral_with` for the requirements on `config`.
+    ///
+    /// # Safety
+    ///
+    /// If `src` is not `'static`, callers must ensure that `mem::forget`
+    /// is never called on the returned `Transfer`.
+    pub unsafe fn to_peripheral_with_nonstatic(
+        mut dma: Channel<C>,
+        config: TransferConfig,
+        src: R,
+        dst: W,
+    ) -> Self
+    where
+        R: DmaReadBuffer<Word = W::Word>,
+        W: PeripheralDestination + DmaRequest<Channel = C>,
+    {
+        assert_eq!(config.direction, Direction::MemoryToPeripheral);
+        assert!(!config.circular);
+        assert!(!config.peripheral_increment);
+        if let Some(size) = config.peripheral_size {
+            assert_eq!(size, W::Word::SIZE);
+        }
+        if let Some(size) = config.memory_size {
+            assert_eq!(size, W::Word::SIZE);
+        }
+
+        dma.configure(&config);
+        {
+            let (src_ptr, src_len) = src.dma_read_buffer();
+            assert!(src_len > 0);
+            assert!(region::dma_accessible(src_ptr, src_len));
+            assert!(region::dma_accessible(dst.address() as *const W::Word, 1));
+            assert!(region::dma_aligned(src_ptr));
+            assert!(region::dma_aligned(dst.address() as *const W::Word));
+
+            dma.set_psize(W::Word::SIZE);
+            dma.set_msize(W::Word::SIZE);
+            dma.set_transfer(dst.address(), src_ptr as *const u8 as u32, src_len);
+        }
+
+        Self::launch(dma, src, dst)
+    }
+
+    unsafe fn start_inner(dma: Channel<C>, config: TransferConfig, src: R, dst: W) -> Self
+    where
+        R: DmaReadBuffer,
+        W: DmaWriteBuffer,
+        R::Word: Word,
+        W::Word: Word,
+    {
+        match Self::try_start_inner(dma, config, src, dst) {
+            Ok(transfer) => transfer,
+            Err(error) => panic!("invalid transfer buffers: {:?}", error.reason),
+        }
+    }
 
-            dma.set_paddr(src_ptr as *const u8 as u32);
-            dma.set_maddr(dst_ptr as *mut u8 as u32);
-            dma.set_ndt(src_len as u16);
+    unsafe fn try_start_inner(
+        mut dma: Channel<C>,
+        config: TransferConfig,
+        src: R,
+        mut dst: W,
+    ) -> Result<Self, TransferError<R, W, C>>
+    where
+        R: DmaReadBuffer,
+        W: DmaWriteBuffer,
+        R::Word: Word,
+        W::Word: Word,
+    {
+        assert_eq!(config.direction, Direction::MemoryToMemory);
+        assert!(!config.circular);
+        if let Some(size) = config.peripheral_size {
+            assert_eq!(size, R::Word::SIZE);
         }
+        if let Some(size) = config.memory_size {
+            assert_eq!(size, W::Word::SIZE);
+        }
+
+        let (src_ptr, src_len) = src.dma_read_buffer();
+        let (dst_ptr, dst_len) = dst.dma_write_buffer();
 
+        let reason = if src_len == 0 {
+            Some(ErrorReason::ZeroLength)
+        } else if dst_len < src_len {
+            Some(ErrorReason::LengthMismatch)
+        } else if !region::dma_accessible(src_ptr, src_len)
+            || !region::dma_accessible(dst_ptr as *const W::Word, dst_len)
+        {
+            Some(ErrorReason::InaccessibleMemory)
+        } else if !region::dma_aligned(src_ptr) || !region::dma_aligned(dst_ptr as *const W::Word) {
+            Some(ErrorReason::Misaligned)
+        } else {
+            None
+        };
+        if let Some(reason) = reason {
+            return Err(TransferError {
+                dma,
+                src,
+                dst,
+                reason,
+                remaining: src_len,
+                transferred: 0,
+            });
+        }
+
+        dma.configure(&config);
+        dma.set_psize(R::Word::SIZE);
+        dma.set_msize(W::Word::SIZE);
+        dma.set_transfer(
+            src_ptr as *const u8 as u32,
+            dst_ptr as *mut u8 as u32,
+            src_len,
+        );
+
+        Ok(Self::launch(dma, src, dst))
+    }
+
+    fn launch(mut dma: Channel<C>, src: R, dst: W) -> Self {
         // Prevent preceding reads/writes on the buffer from being moved past
         // the DMA enable modify (i.e. after the transfer has started).
         atomic::compiler_fence(Ordering::Release);
 
-        dma.enable();
+        let len = dma.remaining_words();
+        // NOTE(unsafe) the constructors checked the buffers, and the transfer
+        // owns them until it has stopped the channel
+        unsafe { dma.enable() };
 
         Transfer {
-            inner: Some(TransferInner { dma, src, dst }),
+            inner: Some(TransferInner { dma, src, dst, len }),
+        }
+    }
+
+    /// Whether the transfer has finished, successfully or not.
+    ///
+    /// If this returns `true`, `poll` and `wait` return right away.
+    pub fn is_done(&self) -> bool {
+        let dma = &self.inner.as_ref().expect("transfer already finished").dma;
+        dma.transfer_error() || (dma.transfer_complete() && dma.remaining_words() == 0)
+    }
+
+    /// Check whether the transfer has finished, without blocking.
+    ///
+    /// Once the transfer has finished, this returns the same result as
+    /// `wait`. The `Transfer` is then empty, and calling any of its methods
+    /// again panics.
+    pub fn poll(&mut self) -> Poll<TransferResult<R, W, C>> {
+        let dma = &mut self.inner.as_mut().expect("transfer already finished").dma;
+
+        // The interrupt handler may arm the next chunk of a long transfer,
+        // so the flags have to be checked without it interfering.
+        let finished = critical_section(|cs| {
+            if dma.transfer_error() {
+                Some(false)
+            } else if dma.transfer_complete() && !dma.rearm(cs) {
+                Some(true)
+            } else {
+                None
+            }
+        });
+
+        match finished {
+            None => Poll::Pending,
+            Some(false) => {
+                let inner = self.inner.take().unwrap();
+                Poll::Ready(Err(inner.fail(ErrorReason::BusError)))
+            }
+            Some(true) => {
+                let mut inner = self.inner.take().unwrap();
+                inner.stop();
+                Poll::Ready(Ok((inner.dma, inner.src, inner.dst)))
+            }
         }
     }
 
-    pub fn wait(mut self) -> Result<(Dma, R, W), ()> {
-        let mut inner = self.inner.take().unwrap();
+    /// Wait for the transfer to finish.
+    ///
+    /// If the transfer fails, the channel and buffers are handed back in
+    /// the `TransferError`.
+    pub fn wait(mut self) -> TransferResult<R, W, C> {
+        loop {
+            if let Poll::Ready(result) = self.poll() {
+                return result;
+            }
 
-        while !inner.dma.transfer_complete() {
-            if inner.dma.transfer_error() {
-                return Err(());
+            // If the transfer raises an interrupt when it completes or fails,
+            // sleep until an interrupt arrives instead of spinning. `wfi` also
+            // wakes up on interrupts masked by the critical section, so
+            // checking the flags inside it makes sure we can't miss it.
+            critical_section(|_| {
+                let dma = &self.inner.as_ref().unwrap().dma;
+                if !self.is_done() && dma.completion_interrupt_enabled() {
+                    asm::wfi();
+                }
+            });
+        }
+    }
+
+    /// Wait for the transfer to finish, giving up after `timeout` ticks of
+    /// `clock`.
+    ///
+    /// On timeout, the transfer is stopped and a `TransferError` with reason
+    /// `ErrorReason::Timeout` is returned. Unlike `wait`, this never sleeps,
+    /// since there is no interrupt signalling the timeout.
+    pub fn wait_timeout<T: TimeSource>(
+        mut self,
+        mut clock: T,
+        timeout: u32,
+    ) -> TransferResult<R, W, C> {
+        let start = clock.now();
+
+        loop {
+            if let Poll::Ready(result) = self.poll() {
+                return result;
+            }
+            if clock.now().wrapping_sub(start) >= timeout {
+                return self.inner.take().unwrap().cancel(ErrorReason::Timeout);
             }
         }
+    }
+
+    /// Number of words left to transfer.
+    ///
+    /// Like all word counts of a `Transfer`, this counts DMA data items, not
+    /// bytes. Every data item is one word of the source and one word of the
+    /// destination, even if their word types differ in size.
+    pub fn remaining(&self) -> usize {
+        let inner = self.inner.as_ref().expect("transfer already finished");
+        inner.dma.remaining_words()
+    }
+
+    /// Number of words transferred so far.
+    ///
+    /// All destination words before this index have been written. See
+    /// `remaining` for how words are counted.
+    pub fn transferred(&self) -> usize {
+        self.inner
+            .as_ref()
+            .expect("transfer already finished")
+            .transferred()
+    }
+
+    /// Stop the transfer and hand back the channel and buffers.
+    ///
+    /// If the transfer finished before it was stopped, this returns the same
+    /// result as `wait`. Otherwise, the buffers are handed back in a
+    /// `TransferError` with reason `ErrorReason::Aborted`. Its `transferred`
+    /// field holds the number of words transferred before the transfer was
+    /// stopped. Only that many words of the destination have been written.
+    /// See `remaining` for how words are counted.
+    pub fn abort(mut self) -> TransferResult<R, W, C> {
+        let inner = self.inner.take().expect("transfer already finished");
+        inner.cancel(ErrorReason::Aborted)
+    }
+
+    /// Turn the transfer into a future that gives up after `timeout` ticks
+    /// of `clock`.
+    ///
+    /// The future resolves to the same result as `wait_timeout`.
+    #[cfg(feature = "async")]
+    pub fn with_timeout<T: TimeSource>(
+        self,
+        mut clock: T,
+        timeout: u32,
+    ) -> WithTimeout<R, W, C, T> {
+        let start = clock.now();
+        WithTimeout {
+            transfer: self,
+            clock,
+            start,
+            timeout,
+        }
+    }
+}
+
+/// Awaiting a `Transfer` resolves to the same result as `Transfer::wait`.
+///
+/// While pending, the transfer completion interrupt is enabled, and the
+/// crate-provided interrupt handler wakes the task. Dropping the future
+/// before it resolves stops the transfer, just like dropping the `Transfer`.
+#[cfg(feature = "async")]
+impl<R, W, C: ChannelId> Future for Transfer<R, W, C> {
+    type Output = TransferResult<R, W, C>;
 
-        inner.stop();
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let inner = this.inner.as_mut().expect("polled after completion");
 
-        Ok((inner.dma, inner.src, inner.dst))
+        waker::WAKERS[C::INDEX].register(cx.waker());
+        inner.dma.enable_completion_interrupt();
+
+        Transfer::poll(this)
     }
 }
 
-struct TransferInner<R, W> {
-    dma: Dma,
+// `Transfer` never pins its buffers, so it can be moved freely even while
+// being polled.
+#[cfg(feature = "async")]
+impl<R, W, C: ChannelId> Unpin for Transfer<R, W, C> {}
+
+struct TransferInner<R, W, C: ChannelId> {
+    dma: Channel<C>,
     src: R,
     dst: W,
+    // number of data items the transfer was started with
+    len: usize,
 }
 
-impl<R, W> TransferInner<R, W> {
+impl<R, W, C: ChannelId> TransferInner<R, W, C> {
     fn stop(&mut self) {
         self.dma.disable();
 
@@ -141,9 +779,43 @@ impl<R, W> TransferInner<R, W> {
         // stopped).
         atomic::compiler_fence(Ordering::Acquire);
     }
+
+    fn transferred(&self) -> usize {
+        self.len - self.dma.remaining_words()
+    }
+
+    /// Stop the transfer before it finished, e.g. after a timeout.
+    fn cancel(mut self, reason: ErrorReason) -> TransferResult<R, W, C> {
+        self.stop();
+
+        // The transfer may have finished or failed right before it was
+        // stopped.
+        if self.dma.transfer_error() {
+            return Err(self.fail(ErrorReason::BusError));
+        }
+        if self.dma.transfer_complete() && self.dma.remaining_words() == 0 {
+            return Ok((self.dma, self.src, self.dst));
+        }
+
+        Err(self.fail(reason))
+    }
+
+    /// Stop the failed transfer and hand back the channel and buffers.
+    fn fail(mut self, reason: ErrorReason) -> TransferError<R, W, C> {
+        self.stop();
+
+        TransferError {
+            remaining: self.dma.remaining_words(),
+            transferred: self.transferred(),
+            dma: self.dma,
+            src: self.src,
+            dst: self.dst,
+            reason,
+        }
+    }
 }
 
-impl<R, W> Drop for Transfer<R, W> {
+impl<R, W, C: ChannelId> Drop for Transfer<R, W, C> {
     fn drop(&mut self) {
         if let Some(mut inner) = self.inner.take() {
             inner.stop();
@@ -0,0 +1,273 @@
+//! Peripheral data registers usable as DMA sources and destinations.
+//!
+//! Peripheral transfers always access the same data register, so they
+//! don't need the buffer traits. Instead, the peripheral side of a transfer
+//! is described by `PeripheralSource` or `PeripheralDestination`.
+
+use crate::{ChannelId, Word};
+
+/// Trait for peripheral data registers the DMA can read from.
+///
+/// # Safety
+///
+/// - `address` must always return the address of the same register.
+/// - That register must be safe to read by the DMA with the size of `Word`
+///   at any time, as long as `self` is not dropped.
+pub unsafe trait PeripheralSource {
+    type Word: Word;
+
+    /// Address of the data register the DMA reads from.
+    fn address(&self) -> u32;
+}
+
+/// Trait for peripheral data registers the DMA can write to.
+///
+/// # Safety
+///
+/// - `address` must always return the address of the same register.
+/// - That register must be safe to write by the DMA with the size of `Word`
+///   at any time, as long as `self` is not dropped.
+pub unsafe trait PeripheralDestination {
+    type Word: Word;
+
+    /// Address of the data register the DMA writes to.
+    fn address(&self) -> u32;
+}
+
+/// Trait for peripheral DMA requests that are wired to a fixed channel.
+///
+/// Peripheral transfers can only be started on the channel given by
+/// `Channel`, so pairing a peripheral with the wrong channel is a compile
+/// error.
+///
+/// On the STM32F303, requests that can be remapped to another channel are
+/// implemented for their default mapping.
+pub trait DmaRequest {
+    type Channel: ChannelId;
+}
+
+/// Receive side of a peripheral, usable as a DMA source.
+///
+/// Creating an `Rx` takes the peripheral and enables its DMA receive
+/// request, so the peripheral should be fully configured beforehand. `free`
+/// disables the request and hands the peripheral back.
+pub struct Rx<P> {
+    // `None` for the receive side created by `split_duplex`
+    periph: Option<P>,
+}
+
+impl<P: private::RxRequest> Rx<P> {
+    pub fn new(periph: P) -> Self {
+        periph.enable_rx_request();
+        Self {
+            periph: Some(periph),
+        }
+    }
+
+    /// Disable the DMA receive request and hand back the peripheral.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `Rx` was created by `split_duplex`. Use `join_duplex`
+    /// to get the peripheral back in that case.
+    pub fn free(self) -> P {
+        let periph = self.periph.expect("`Rx` of a split peripheral");
+        periph.disable_rx_request();
+        periph
+    }
+}
+
+unsafe impl<P: private::RxRequest> PeripheralSource for Rx<P> {
+    type Word = P::Word;
+
+    fn address(&self) -> u32 {
+        P::rx_address()
+    }
+}
+
+/// Transmit side of a peripheral, usable as a DMA destination.
+///
+/// Creating a `Tx` takes the peripheral and enables its DMA transmit
+/// request, so the peripheral should be fully configured beforehand. `free`
+/// disables the request and hands the peripheral back.
+pub struct Tx<P> {
+    periph: P,
+    // whether the peripheral was split by `split_duplex`, so its receive
+    // side is still in use
+    split: bool,
+}
+
+impl<P: private::TxRequest> Tx<P> {
+    pub fn new(periph: P) -> Self {
+        periph.enable_tx_request();
+        Self {
+            periph,
+            split: false,
+        }
+    }
+
+    /// Disable the DMA transmit request and hand back the peripheral.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `Tx` was created by `split_duplex`. Use `join_duplex`
+    /// to get the peripheral back in that case.
+    pub fn free(self) -> P {
+        assert!(!self.split, "`Tx` of a split peripheral");
+        self.periph.disable_tx_request();
+        self.periph
+    }
+}
+
+unsafe impl<P: private::TxRequest> PeripheralDestination for Tx<P> {
+    type Word = P::Word;
+
+    fn address(&self) -> u32 {
+        P::tx_address()
+    }
+}
+
+/// Split a full-duplex peripheral into its receive and transmit sides.
+///
+/// This enables both DMA requests of the peripheral, so it should be fully
+/// configured beforehand. The peripheral is kept by the `Tx`, and can only
+/// be recovered by passing both sides to `join_duplex`.
+pub fn split_duplex<P>(periph: P) -> (Rx<P>, Tx<P>)
+where
+    P: private::RxRequest + private::TxRequest<Word = <P as private::RxRequest>::Word>,
+{
+    periph.enable_rx_request();
+    periph.enable_tx_request();
+
+    let rx = Rx { periph: None };
+    let tx = Tx {
+        periph,
+        split: true,
+    };
+    (rx, tx)
+}
+
+/// Disable both DMA requests of a split peripheral and hand it back.
+///
+/// # Panics
+///
+/// Panics if `rx` and `tx` were not created by `split_duplex`.
+pub fn join_duplex<P>(rx: Rx<P>, tx: Tx<P>) -> P
+where
+    P: private::RxRequest + private::TxRequest<Word = <P as private::RxRequest>::Word>,
+{
+    assert!(rx.periph.is_none() && tx.split);
+    tx.periph.disable_rx_request();
+    tx.periph.disable_tx_request();
+    tx.periph
+}
+
+/// This module contains the per-peripheral details used by `Rx` and `Tx`.
+///
+/// It is kept private to prevent others from implementing these traits.
+/// Third-party code should impl `PeripheralSource` and
+/// `PeripheralDestination` directly.
+mod private {
+    use super::{DmaRequest, Rx, Tx};
+    use crate::{dma1, dma2, pac, Word};
+
+    pub trait RxRequest {
+        type Word: Word;
+
+        fn enable_rx_request(&self);
+
+        fn disable_rx_request(&self);
+
+        fn rx_address() -> u32;
+    }
+
+    pub trait TxRequest {
+        type Word: Word;
+
+        fn enable_tx_request(&self);
+
+        fn disable_tx_request(&self);
+
+        fn tx_address() -> u32;
+    }
+
+    macro_rules! rx {
+        ( $( $PERIPH:ident: ($word:ty, $reg:ident, $cr:ident.$dmaen:ident, $C:ty), )+ ) => {
+            $(
+                impl DmaRequest for Rx<pac::$PERIPH> {
+                    type Channel = $C;
+                }
+
+                impl RxRequest for pac::$PERIPH {
+                    type Word = $word;
+
+                    fn enable_rx_request(&self) {
+                        self.$cr.modify(|_, w| w.$dmaen().set_bit());
+                    }
+
+                    fn disable_rx_request(&self) {
+                        self.$cr.modify(|_, w| w.$dmaen().clear_bit());
+                    }
+
+                    fn rx_address() -> u32 {
+                        // NOTE(unsafe) we only take the address of the register
+                        unsafe { &(*pac::$PERIPH::ptr()).$reg as *const _ as u32 }
+                    }
+                }
+            )+
+        };
+    }
+
+    rx!(
+        USART1: (u8, rdr, cr3.dmar, dma1::C5),
+        USART2: (u8, rdr, cr3.dmar, dma1::C6),
+        USART3: (u8, rdr, cr3.dmar, dma1::C3),
+        UART4: (u8, rdr, cr3.dmar, dma2::C3),
+        SPI1: (u8, dr, cr2.rxdmaen, dma1::C2),
+        SPI2: (u8, dr, cr2.rxdmaen, dma1::C4),
+        SPI3: (u8, dr, cr2.rxdmaen, dma2::C1),
+        ADC1: (u16, dr, cfgr.dmaen, dma1::C1),
+        ADC2: (u16, dr, cfgr.dmaen, dma2::C1),
+        ADC3: (u16, dr, cfgr.dmaen, dma2::C5),
+        ADC4: (u16, dr, cfgr.dmaen, dma2::C2),
+    );
+
+    macro_rules! tx {
+        ( $( $PERIPH:ident: ($word:ty, $reg:ident, $cr:ident.$dmaen:ident, $C:ty), )+ ) => {
+            $(
+                impl DmaRequest for Tx<pac::$PERIPH> {
+                    type Channel = $C;
+                }
+
+                impl TxRequest for pac::$PERIPH {
+                    type Word = $word;
+
+                    fn enable_tx_request(&self) {
+                        self.$cr.modify(|_, w| w.$dmaen().set_bit());
+                    }
+
+                    fn disable_tx_request(&self) {
+                        self.$cr.modify(|_, w| w.$dmaen().clear_bit());
+                    }
+
+                    fn tx_address() -> u32 {
+                        // NOTE(unsafe) we only take the address of the register
+                        unsafe { &(*pac::$PERIPH::ptr()).$reg as *const _ as u32 }
+                    }
+                }
+            )+
+        };
+    }
+
+    tx!(
+        USART1: (u8, tdr, cr3.dmat, dma1::C4),
+        USART2: (u8, tdr, cr3.dmat, dma1::C7),
+        USART3: (u8, tdr, cr3.dmat, dma1::C2),
+        UART4: (u8, tdr, cr3.dmat, dma2::C5),
+        SPI1: (u8, dr, cr2.txdmaen, dma1::C3),
+        SPI2: (u8, dr, cr2.txdmaen, dma1::C5),
+        SPI3: (u8, dr, cr2.txdmaen, dma2::C2),
+        // DAC channel 1, 12-bit right-aligned data
+        DAC: (u16, dhr12r1, cr.dmaen1, dma2::C3),
+    );
+}
@@ -0,0 +1,72 @@
+//! Memory regions the DMA controllers can access, and the alignment they
+//! need.
+//!
+//! On the STM32F303, the DMA controllers are masters on the AHB bus matrix.
+//! They reach the flash, the SRAM and the peripherals, but not the CCM RAM,
+//! which is only connected to the CPU. Buffers outside these regions make a
+//! transfer fail with a bus error, or silently do nothing.
+
+use core::mem;
+
+/// A range of addresses the DMA can access.
+struct Region {
+    start: usize,
+    // exclusive
+    end: usize,
+}
+
+/// DMA-accessible regions of the STM32F303.
+///
+/// The sizes of the flash and SRAM are those of the largest variants
+/// (STM32F303xE). Devices with less memory don't map anything else there.
+#[cfg(not(test))]
+const REGIONS: &[Region] = &[
+    // Flash
+    Region {
+        start: 0x0800_0000,
+        end: 0x0808_0000,
+    },
+    // SRAM
+    Region {
+        start: 0x2000_0000,
+        end: 0x2001_0000,
+    },
+    // APB1, APB2, AHB1, AHB2 and AHB3 peripherals
+    Region {
+        start: 0x4000_0000,
+        end: 0x5000_0800,
+    },
+];
+
+/// Host tests use mock channels, which accept buffers anywhere in memory.
+#[cfg(test)]
+const REGIONS: &[Region] = &[Region {
+    start: 0,
+    end: usize::MAX,
+}];
+
+/// Whether the DMA can access all of the `len` words starting at `ptr`.
+pub(crate) fn dma_accessible<T>(ptr: *const T, len: usize) -> bool {
+    let start = ptr as usize;
+    let end = match len
+        .checked_mul(mem::size_of::<T>())
+        .and_then(|size| start.checked_add(size))
+    {
+        Some(end) => end,
+        None => return false,
+    };
+
+    REGIONS
+        .iter()
+        .any(|region| region.start <= start && end <= region.end)
+}
+
+/// Whether `ptr` is aligned to the size of `T`.
+///
+/// The DMA ignores the low address bits not matching its data size, so a
+/// misaligned buffer would be accessed at the wrong address.
+// `usize::is_multiple_of` would need Rust 1.87
+#[allow(clippy::manual_is_multiple_of)]
+pub(crate) fn dma_aligned<T>(ptr: *const T) -> bool {
+    (ptr as usize) % mem::size_of::<T>().max(1) == 0
+}
@@ -0,0 +1,359 @@
+//! Host tests, driving transfers on mock channels.
+//!
+//! A mock channel's registers live in RAM, so the tests play the part of
+//! the DMA controller: they update the flags and counters the way the
+//! hardware would, and call the channel's interrupt handler.
+
+extern crate std;
+
+use crate::{channel::CHANNELS, pac, Channel, ChannelId};
+use core::{mem, ptr};
+
+const WORDS: usize = mem::size_of::<pac::dma1::RegisterBlock>() / 4;
+
+/// Register blocks of the mock channels, indexed by `ChannelId::INDEX`.
+static mut REGISTERS: [[u32; WORDS]; CHANNELS] = [[0; WORDS]; CHANNELS];
+
+// word offsets of the registers used by channel 1
+const ISR: usize = 0;
+const CCR: usize = 2;
+const CNDTR: usize = 3;
+
+// channel 1 flags
+const GIF: u32 = 1 << 0;
+const TCIF: u32 = 1 << 1;
+const TEIF: u32 = 1 << 3;
+
+// channel 1 configuration bits
+const EN: u32 = 1 << 0;
+const TCIE: u32 = 1 << 1;
+const TEIE: u32 = 1 << 3;
+
+/// A mock channel, acting as channel 1 of its own DMA controller.
+///
+/// Every test uses a different `I`, so tests running in parallel never
+/// share any per-channel state. The real channels are never used by the
+/// tests, so the mocks may reuse their indices.
+struct Mock<const I: usize>;
+
+unsafe impl<const I: usize> ChannelId for Mock<I> {
+    const NUMBER: u8 = 1;
+    const INDEX: usize = I;
+    const INTERRUPT: pac::Interrupt = pac::Interrupt::DMA1_CH1;
+
+    fn registers() -> *const pac::dma1::RegisterBlock {
+        // NOTE(unsafe) only the address is taken, no reference is created
+        unsafe { ptr::addr_of!(REGISTERS[I]) as *const _ }
+    }
+
+    fn unmask_interrupt() {}
+}
+
+impl<const I: usize> Mock<I> {
+    fn channel() -> Channel<Self> {
+        // NOTE(unsafe) each test creates only one channel for its mock
+        unsafe { Channel::steal() }
+    }
+
+    fn read(offset: usize) -> u32 {
+        let reg = Self::registers() as *const u32;
+        // NOTE(unsafe) `offset` is within the register block
+        unsafe { reg.add(offset).read_volatile() }
+    }
+
+    fn write(offset: usize, value: u32) {
+        let reg = Self::registers() as *mut u32;
+        // NOTE(unsafe) `offset` is within the register block
+        unsafe { reg.add(offset).write_volatile(value) }
+    }
+
+    fn enabled() -> bool {
+        Self::read(CCR) & EN != 0
+    }
+
+    /// Finish the current chunk, like the DMA does.
+    fn complete() {
+        Self::write(CNDTR, 0);
+        Self::write(ISR, GIF | TCIF);
+        Channel::<Self>::on_interrupt();
+    }
+
+    /// Report a bus error, like the DMA does.
+    fn fail() {
+        Self::write(ISR, GIF | TEIF);
+        Channel::<Self>::on_interrupt();
+    }
+}
+
+fn leak<T>(value: T) -> &'static mut T {
+    std::boxed::Box::leak(std::boxed::Box::new(value))
+}
+
+fn static_buffers(len: usize) -> (&'static [u8], &'static mut [u8]) {
+    let src = std::vec![0xaa; len].leak();
+    let dst = std::vec![0; len].leak();
+    (src, dst)
+}
+
+#[test]
+fn transfer_finishes_on_completion() {
+    type M = Mock<0>;
+    let (src, dst) = static_buffers(16);
+    let mut transfer = crate::Transfer::start_on(M::channel(), src, dst);
+
+    assert!(M::enabled());
+    assert!(transfer.poll().is_pending());
+
+    M::complete();
+    assert!(transfer.is_done());
+    assert!(transfer.poll().is_ready());
+    assert!(!M::enabled());
+}
+
+#[test]
+fn transfer_reports_bus_error() {
+    type M = Mock<1>;
+    let (src, dst) = static_buffers(16);
+    let transfer = crate::Transfer::start_on(M::channel(), src, dst);
+
+    M::fail();
+    let error = match transfer.wait() {
+        Err(error) => error,
+        Ok(_) => panic!("transfer didn't fail"),
+    };
+    assert_eq!(error.reason, crate::ErrorReason::BusError);
+    assert_eq!(error.remaining, 16);
+    assert!(!M::enabled());
+}
+
+#[test]
+fn aborted_transfer_reports_remaining_words() {
+    type M = Mock<8>;
+    let (src, dst) = static_buffers(16);
+    let transfer = crate::Transfer::start_on(M::channel(), src, dst);
+
+    M::write(CNDTR, 10);
+    let error = match transfer.abort() {
+        Err(error) => error,
+        Ok(_) => panic!("transfer wasn't aborted"),
+    };
+    assert_eq!(error.reason, crate::ErrorReason::Aborted);
+    assert_eq!(error.remaining, 10);
+    assert_eq!(error.transferred, 6);
+    assert!(!M::enabled());
+}
+
+#[test]
+fn dropping_transfer_stops_it() {
+    type M = Mock<2>;
+    let (src, dst) = static_buffers(16);
+    let transfer = crate::Transfer::start_on(M::channel(), src, dst);
+
+    drop(transfer);
+    assert!(!M::enabled());
+}
+
+#[test]
+fn long_transfer_is_rearmed_from_interrupt() {
+    type M = Mock<6>;
+    let (src, dst) = static_buffers(70_000);
+    let transfer = crate::Transfer::start_on(M::channel(), src, dst);
+
+    // long transfers always interrupt on completion of a chunk
+    assert_ne!(M::read(CCR) & TCIE, 0);
+    assert_eq!(M::read(CNDTR), 65_535);
+
+    M::complete();
+    assert!(M::enabled());
+    assert_eq!(M::read(CNDTR), 70_000 - 65_535);
+    assert!(!transfer.is_done());
+    assert_eq!(transfer.transferred(), 65_535);
+
+    M::complete();
+    assert!(transfer.is_done());
+    assert!(transfer.wait().is_ok());
+}
+
+#[test]
+fn long_transfer_reports_bus_error_in_later_chunk() {
+    type M = Mock<9>;
+    let (src, dst) = static_buffers(70_000);
+    let transfer = crate::Transfer::start_on(M::channel(), src, dst);
+
+    // a failing chunk has to wake up `wait`, too
+    assert_ne!(M::read(CCR) & TEIE, 0);
+
+    M::complete();
+    M::fail();
+    let error = match transfer.wait() {
+        Err(error) => error,
+        Ok(_) => panic!("transfer didn't fail"),
+    };
+    assert_eq!(error.reason, crate::ErrorReason::BusError);
+    assert_eq!(error.remaining, 70_000 - 65_535);
+    assert!(!M::enabled());
+}
+
+#[test]
+fn arrays_of_any_length_are_buffers() {
+    use crate::{DmaReadBuffer, DmaWriteBuffer};
+
+    let bytes: &'static [u8; 256] = leak([0; 256]);
+    assert_eq!(bytes.dma_read_buffer().1, 256);
+
+    let mut words = leak([0u16; 1024]);
+    assert_eq!(words.dma_write_buffer().1, 1024);
+
+    // nested arrays count their innermost words
+    let mut samples = leak([[0u16; 2]; 512]);
+    assert_eq!(samples.dma_write_buffer().1, 1024);
+    assert_eq!((&*samples).dma_read_buffer().1, 1024);
+}
+
+#[test]
+fn long_array_transfer_finishes() {
+    type M = Mock<10>;
+    let src: &'static [u16; 1024] = leak([0xaa; 1024]);
+    let dst = leak([[0u16; 2]; 512]);
+    let transfer = crate::Transfer::start_on(M::channel(), src, dst);
+
+    assert_eq!(transfer.remaining(), 1024);
+    assert_eq!(M::read(CNDTR), 1024);
+
+    M::complete();
+    assert!(transfer.wait().is_ok());
+}
+
+/// A peripheral whose DMA request is wired to a mock channel.
+struct MockPeripheral<const I: usize>(&'static u8);
+
+unsafe impl<const I: usize> crate::PeripheralSource for MockPeripheral<I> {
+    type Word = u8;
+
+    fn address(&self) -> u32 {
+        self.0 as *const u8 as u32
+    }
+}
+
+impl<const I: usize> crate::DmaRequest for MockPeripheral<I> {
+    type Channel = Mock<I>;
+}
+
+#[test]
+#[should_panic(expected = "!config.transfer_complete_interrupt")]
+fn circular_transfer_rejects_completion_interrupts() {
+    type M = Mock<7>;
+    let config = crate::TransferConfig::builder()
+        .direction(crate::Direction::PeripheralToMemory)
+        .peripheral_increment(false)
+        .circular(true)
+        .transfer_complete_interrupt(true)
+        .build()
+        .unwrap();
+    let (_, dst) = static_buffers(16);
+    crate::CircularTransfer::start_with(M::channel(), config, MockPeripheral(leak(0)), dst);
+}
+
+#[test]
+fn pingpong_reports_bus_error() {
+    type M = Mock<11>;
+    let (_, dst) = static_buffers(16);
+    let mut pingpong = crate::PingPong::start(M::channel(), MockPeripheral(leak(0)), dst);
+
+    M::write(ISR, GIF | TEIF);
+    let mut half = [0; 8];
+    assert_eq!(
+        pingpong.read(&mut half),
+        Err(crate::PingPongError::BusError)
+    );
+}
+
+#[cfg(feature = "async")]
+mod future {
+    use super::*;
+    use core::{
+        future::Future,
+        pin::Pin,
+        sync::atomic::{AtomicUsize, Ordering},
+        task::{Context, Poll, Waker},
+    };
+    use std::{sync::Arc, task::Wake};
+
+    /// Waker counting how often it was woken.
+    #[derive(Default)]
+    struct CountingWaker(AtomicUsize);
+
+    impl CountingWaker {
+        fn wakes(&self) -> usize {
+            self.0.load(Ordering::SeqCst)
+        }
+    }
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fn waker() -> (Arc<CountingWaker>, Waker) {
+        let counter = Arc::new(CountingWaker::default());
+        let waker = Waker::from(counter.clone());
+        (counter, waker)
+    }
+
+    fn poll<F: Future + Unpin>(future: &mut F, waker: &Waker) -> Poll<F::Output> {
+        Pin::new(future).poll(&mut Context::from_waker(waker))
+    }
+
+    #[test]
+    fn future_is_woken_on_completion() {
+        type M = Mock<3>;
+        let (counter, waker) = waker();
+        let (src, dst) = static_buffers(16);
+        let mut transfer = crate::Transfer::start_on(M::channel(), src, dst);
+
+        assert!(poll(&mut transfer, &waker).is_pending());
+        assert_ne!(M::read(CCR) & TCIE, 0);
+        assert_eq!(counter.wakes(), 0);
+
+        M::complete();
+        assert_eq!(counter.wakes(), 1);
+        // the handler masks the interrupt, but leaves the flag alone
+        assert_eq!(M::read(CCR) & TCIE, 0);
+
+        match poll(&mut transfer, &waker) {
+            Poll::Ready(Ok(_)) => {}
+            _ => panic!("transfer didn't finish"),
+        }
+        assert!(!M::enabled());
+    }
+
+    #[test]
+    fn future_resolves_to_bus_error() {
+        type M = Mock<4>;
+        let (counter, waker) = waker();
+        let (src, dst) = static_buffers(16);
+        let mut transfer = crate::Transfer::start_on(M::channel(), src, dst);
+
+        assert!(poll(&mut transfer, &waker).is_pending());
+        M::fail();
+        assert_eq!(counter.wakes(), 1);
+
+        match poll(&mut transfer, &waker) {
+            Poll::Ready(Err(error)) => assert_eq!(error.reason, crate::ErrorReason::BusError),
+            _ => panic!("transfer didn't fail"),
+        }
+    }
+
+    #[test]
+    fn dropping_future_stops_transfer() {
+        type M = Mock<5>;
+        let (_counter, waker) = waker();
+        let (src, dst) = static_buffers(16);
+        let mut transfer = crate::Transfer::start_on(M::channel(), src, dst);
+
+        assert!(poll(&mut transfer, &waker).is_pending());
+        drop(transfer);
+        assert!(!M::enabled());
+    }
+}
@@ -0,0 +1,125 @@
+//! Timeouts for waiting on transfers.
+//!
+//! Waiting with a timeout needs a notion of time, which the DMA doesn't
+//! have. It is provided by the caller through a `TimeSource`, which is
+//! implemented for the DWT cycle counter and for SysTick (via
+//! `SysTickClock`). Monotonic timers of HALs or frameworks can implement it
+//! as well.
+
+#[cfg(feature = "async")]
+use crate::{ChannelId, ErrorReason, Transfer, TransferResult};
+#[cfg(feature = "async")]
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use cortex_m::peripheral::{DWT, SYST};
+
+/// Trait for free-running tick counters.
+pub trait TimeSource {
+    /// Current value of the counter.
+    ///
+    /// The counter must count up and wrap around at `u32::MAX`.
+    fn now(&mut self) -> u32;
+}
+
+impl<T: TimeSource> TimeSource for &mut T {
+    fn now(&mut self) -> u32 {
+        (**self).now()
+    }
+}
+
+/// The DWT cycle counter, counting CPU cycles.
+///
+/// The cycle counter must have been enabled via `DWT::enable_cycle_counter`.
+impl TimeSource for DWT {
+    fn now(&mut self) -> u32 {
+        DWT::get_cycle_count()
+    }
+}
+
+/// SysTick, extended to a 32-bit counter counting SysTick clock ticks.
+///
+/// SysTick only counts down from its reload value, so wrap-arounds are
+/// counted in software. For this to work, `now` must be called at least
+/// once per SysTick period, and nothing else may read the SysTick control
+/// register, since doing so clears the wrap-around flag.
+pub struct SysTickClock {
+    syst: SYST,
+    base: u32,
+}
+
+impl SysTickClock {
+    /// Start SysTick with the given reload value and use it as time source.
+    ///
+    /// The clock source of SysTick is left unchanged.
+    pub fn new(mut syst: SYST, reload: u32) -> Self {
+        syst.disable_counter();
+        syst.set_reload(reload);
+        syst.clear_current();
+        syst.has_wrapped();
+        syst.enable_counter();
+
+        Self { syst, base: 0 }
+    }
+
+    /// Stop SysTick and release it.
+    pub fn free(mut self) -> SYST {
+        self.syst.disable_counter();
+        self.syst
+    }
+}
+
+impl TimeSource for SysTickClock {
+    fn now(&mut self) -> u32 {
+        let reload = SYST::get_reload();
+        let mut current = SYST::get_current();
+        if self.syst.has_wrapped() {
+            self.base = self.base.wrapping_add(reload + 1);
+            // the first read may have been before the wrap-around
+            current = SYST::get_current();
+        }
+        self.base.wrapping_add(reload - current)
+    }
+}
+
+/// Future returned by `Transfer::with_timeout`.
+///
+/// There is no interrupt signalling the timeout, so while pending, the
+/// future asks to be polled again right away. Executors that sleep until
+/// an interrupt arrives therefore need some periodic interrupt, like the
+/// SysTick exception, for the timeout to be noticed.
+#[cfg(feature = "async")]
+pub struct WithTimeout<R, W, C: ChannelId, T> {
+    pub(crate) transfer: Transfer<R, W, C>,
+    pub(crate) clock: T,
+    pub(crate) start: u32,
+    pub(crate) timeout: u32,
+}
+
+#[cfg(feature = "async")]
+impl<R, W, C: ChannelId, T: TimeSource> Future for WithTimeout<R, W, C, T> {
+    type Output = TransferResult<R, W, C>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        match Future::poll(Pin::new(&mut this.transfer), cx) {
+            Poll::Ready(result) => return Poll::Ready(result),
+            Poll::Pending => {}
+        }
+
+        if this.clock.now().wrapping_sub(this.start) >= this.timeout {
+            let inner = this.transfer.inner.take().unwrap();
+            return Poll::Ready(inner.cancel(ErrorReason::Timeout));
+        }
+
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+// Neither the transfer nor the clock are ever pinned.
+#[cfg(feature = "async")]
+impl<R, W, C: ChannelId, T> Unpin for WithTimeout<R, W, C, T> {}
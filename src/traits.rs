@@ -8,6 +8,77 @@
 use core::ops::{Deref, DerefMut};
 use stable_deref_trait::StableDeref;
 
+/// Size of a single data item moved by the DMA.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WordSize {
+    Bits8,
+    Bits16,
+    Bits32,
+}
+
+/// Trait for word types the DMA can move as a single data item.
+///
+/// `Transfer` uses this to program the peripheral and memory data sizes
+/// from the `Word` types of the given buffers.
+///
+/// # Safety
+///
+/// `SIZE` must match the size of the implementing type. Otherwise the DMA
+/// would access more memory per data item than the buffer provides.
+pub unsafe trait Word {
+    const SIZE: WordSize;
+}
+
+unsafe impl Word for u8 {
+    const SIZE: WordSize = WordSize::Bits8;
+}
+
+unsafe impl Word for u16 {
+    const SIZE: WordSize = WordSize::Bits16;
+}
+
+unsafe impl Word for u32 {
+    const SIZE: WordSize = WordSize::Bits32;
+}
+
+unsafe impl Word for i8 {
+    const SIZE: WordSize = WordSize::Bits8;
+}
+
+unsafe impl Word for i16 {
+    const SIZE: WordSize = WordSize::Bits16;
+}
+
+unsafe impl Word for i32 {
+    const SIZE: WordSize = WordSize::Bits32;
+}
+
+unsafe impl Word for f32 {
+    const SIZE: WordSize = WordSize::Bits32;
+}
+
+/// Trait for word types the buffer traits are implemented for.
+///
+/// Arrays, slices and other targets of `DmaReadBuffer` and `DmaWriteBuffer`
+/// are built from these words. Besides the primitive integers and `f32`, it
+/// can be implemented for `#[repr(transparent)]` newtypes around them, most
+/// easily via `#[derive(DmaWord)]` (with the `derive` feature).
+///
+/// # Safety
+///
+/// Types that implement this trait must be valid for every possible byte
+/// pattern. This is to ensure that, whatever DMA writes into the buffer,
+/// we won't get UB due to invalid values.
+pub unsafe trait DmaWord: Word {}
+
+unsafe impl DmaWord for u8 {}
+unsafe impl DmaWord for u16 {}
+unsafe impl DmaWord for u32 {}
+unsafe impl DmaWord for i8 {}
+unsafe impl DmaWord for i16 {}
+unsafe impl DmaWord for i32 {}
+unsafe impl DmaWord for f32 {}
+
 /// Trait for buffers that can be given to DMA for reading.
 ///
 /// # Safety
@@ -63,6 +134,59 @@ pub unsafe trait DmaWriteBuffer {
     fn dma_write_buffer(&mut self) -> (*mut Self::Word, usize);
 }
 
+/// Trait for the alignment markers used by `Aligned`.
+///
+/// This is sealed, only `A1`, `A4` and `A32` implement it.
+pub trait Alignment: private::Sealed {}
+
+/// Marker for 1-byte alignment.
+#[repr(align(1))]
+pub struct A1;
+
+/// Marker for 4-byte alignment.
+#[repr(align(4))]
+pub struct A4;
+
+/// Marker for 32-byte alignment.
+#[repr(align(32))]
+pub struct A32;
+
+impl Alignment for A1 {}
+impl Alignment for A4 {}
+impl Alignment for A32 {}
+
+/// Wrapper that aligns `T` to (at least) the alignment given by `A`.
+///
+/// This can be used to build buffers for peripherals or caches that need a
+/// larger alignment than that of their word type. DMA transfers on an
+/// `Aligned` buffer cover exactly the wrapped value, not any padding added
+/// for the alignment.
+#[repr(C)]
+pub struct Aligned<A: Alignment, T: ?Sized> {
+    _align: [A; 0],
+    pub value: T,
+}
+
+impl<A: Alignment, T> Aligned<A, T> {
+    pub const fn new(value: T) -> Self {
+        Self { _align: [], value }
+    }
+}
+
+impl<A: Alignment, T: ?Sized> Deref for Aligned<A, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<A: Alignment, T: ?Sized> DerefMut for Aligned<A, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
 // Blanked implementations for common DMA buffer types.
 
 unsafe impl<B, T> DmaReadBuffer for B
@@ -95,20 +219,14 @@ where
 /// It is kept private to prevent others from implementing these traits.
 /// Third-party code should impl the public DMA buffer traits directly.
 mod private {
+    use super::{Aligned, Alignment, DmaWord, A1, A32, A4};
     use core::mem::{self, MaybeUninit};
 
-    /// Trait for DMA word types used by the blanket implementations.
-    ///
-    /// # Safety
-    ///
-    /// Types that implement this trait must be valid for every possible byte
-    /// pattern. This is to ensure that, whatever DMA writes into the buffer,
-    /// we won't get UB due to invalid values.
-    pub unsafe trait DmaWord {}
+    pub trait Sealed {}
 
-    unsafe impl DmaWord for u8 {}
-    unsafe impl DmaWord for u16 {}
-    unsafe impl DmaWord for u32 {}
+    impl Sealed for A1 {}
+    impl Sealed for A4 {}
+    impl Sealed for A32 {}
 
     /// Trait for `Deref` targets used by the blanket `DmaReadBuffer` impl.
     ///
@@ -168,29 +286,35 @@ mod private {
         type Word = T::Word;
     }
 
-    // Support DMA reads and writes on arrays.
+    // Support DMA reads and writes on arrays of any length, including
+    // nested arrays.
+
+    unsafe impl<T: DmaReadTarget, const N: usize> DmaReadTarget for [T; N] {
+        type Word = T::Word;
+    }
+
+    unsafe impl<T: DmaWriteTarget, const N: usize> DmaWriteTarget for [T; N] {
+        type Word = T::Word;
+    }
+
+    // Support DMA reads and writes on over-aligned data. The buffer is the
+    // wrapped value only, excluding any trailing padding.
 
-    macro_rules! dma_target_array_impls {
-        ( $( $i:expr, )+ ) => {
-            $(
-                unsafe impl<T: DmaReadTarget> DmaReadTarget for [T; $i] {
-                    type Word = T::Word;
-                }
+    unsafe impl<A: Alignment, T: DmaReadTarget + ?Sized> DmaReadTarget for Aligned<A, T> {
+        type Word = T::Word;
 
-                unsafe impl<T: DmaWriteTarget> DmaWriteTarget for [T; $i] {
-                    type Word = T::Word;
-                }
-            )+
-        };
+        fn as_dma_read_buffer(&self) -> (*const Self::Word, usize) {
+            self.value.as_dma_read_buffer()
+        }
     }
 
-    #[rustfmt::skip]
-    dma_target_array_impls!(
-        0,  1,  2,  3,  4,  5,  6,  7,  8,  9,
-        10, 11, 12, 13, 14, 15, 16, 17, 18, 19,
-        20, 21, 22, 23, 24, 25, 26, 27, 28, 29,
-        30, 31, 32,
-    );
+    unsafe impl<A: Alignment, T: DmaWriteTarget + ?Sized> DmaWriteTarget for Aligned<A, T> {
+        type Word = T::Word;
+
+        fn as_dma_write_buffer(&mut self) -> (*mut Self::Word, usize) {
+            self.value.as_dma_write_buffer()
+        }
+    }
 
     // Support DMA writes on MaybeUninit data.
 
@@ -0,0 +1,86 @@
+//! Per-channel waker slots, used to wake transfer futures from the DMA
+//! interrupt handlers.
+
+use crate::channel::CHANNELS;
+use core::{
+    cell::UnsafeCell,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::Waker,
+};
+
+#[allow(clippy::declare_interior_mutable_const)]
+const EMPTY: WakerSlot = WakerSlot::new();
+
+/// One waker slot per DMA channel, indexed by `ChannelId::INDEX`.
+pub(crate) static WAKERS: [WakerSlot; CHANNELS] = [EMPTY; CHANNELS];
+
+const IDLE: usize = 0;
+const REGISTERING: usize = 0b01;
+const WAKING: usize = 0b10;
+
+/// A slot holding a single `Waker`.
+///
+/// Registering and waking may race (e.g. when the interrupt handler
+/// preempts a `poll`). The `state` field arbitrates access to the waker:
+/// whoever sets their bit while the other one's is clear gets access. If
+/// `wake` loses the race, `register` notices and wakes the new waker
+/// itself.
+pub(crate) struct WakerSlot {
+    state: AtomicUsize,
+    waker: UnsafeCell<Option<Waker>>,
+}
+
+// NOTE(unsafe) access to `waker` is synchronized through `state`
+unsafe impl Sync for WakerSlot {}
+
+impl WakerSlot {
+    const fn new() -> Self {
+        Self {
+            state: AtomicUsize::new(IDLE),
+            waker: UnsafeCell::new(None),
+        }
+    }
+
+    /// Store `waker`, replacing any previously registered one.
+    pub(crate) fn register(&self, waker: &Waker) {
+        match self
+            .state
+            .compare_exchange(IDLE, REGISTERING, Ordering::Acquire, Ordering::Acquire)
+        {
+            Ok(_) => {
+                // NOTE(unsafe) we hold the `REGISTERING` bit
+                unsafe { *self.waker.get() = Some(waker.clone()) };
+
+                let released = self.state.compare_exchange(
+                    REGISTERING,
+                    IDLE,
+                    Ordering::AcqRel,
+                    Ordering::Acquire,
+                );
+                if released.is_err() {
+                    // `wake` was called while we were registering
+                    let waker = unsafe { (*self.waker.get()).take() };
+                    self.state.store(IDLE, Ordering::Release);
+                    if let Some(waker) = waker {
+                        waker.wake();
+                    }
+                }
+            }
+            // `wake` is in progress, make sure we get polled again
+            Err(WAKING) => waker.wake_by_ref(),
+            Err(_) => {}
+        }
+    }
+
+    /// Wake the registered waker, if any.
+    pub(crate) fn wake(&self) {
+        if self.state.fetch_or(WAKING, Ordering::AcqRel) == IDLE {
+            // NOTE(unsafe) we hold the `WAKING` bit
+            let waker = unsafe { (*self.waker.get()).take() };
+            self.state.fetch_and(!WAKING, Ordering::Release);
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        }
+    }
+}